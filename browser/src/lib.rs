@@ -1,5 +1,9 @@
+use std::collections::HashMap;
 use std::sync::Once;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use processor::core::clock::SystemClock;
+use processor::core::enriching::CalendarField;
 use processor::core::State;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -7,7 +11,7 @@ use tracing_web::MakeWebConsoleWriter;
 use wasm_bindgen::JsValue;
 use wasm_bindgen::prelude::wasm_bindgen;
 use common::event::Event;
-use common::rule_result::RuleResultKind;
+use common::rule_result::ResultRecord;
 mod parsing;
 
 static INIT: Once = Once::new();
@@ -48,16 +52,40 @@ pub async fn process_event(js_input: JsValue) -> Option<JsValue> {
                 return None;
             }
         };
-        State::new(sr, cr, rr)
+        let calendars = match parsing::parse_calendars(input.calendars_str.as_str()) {
+            Ok(val) => val,
+            Err(err) => {
+                tracing::error!("invalid calendars string - details {err}");
+                return None;
+            }
+        };
+        let mut state = State::new(sr, cr, rr);
+        if let Err(name) = state.link_calendars(calendars) {
+            tracing::error!("rules reference unknown calendar {name}");
+            return None;
+        }
+        state
     } else {
         State::load(input.game_state.as_str())
     };
+    if let Some(start_date) = input.start_date {
+        state.enricher.add(CalendarField::DaysSinceStart(start_date));
+    }
+
+    state.clock = Some(Box::new(SystemClock));
 
-    let results = state.update(&input.event);
+    let results = match state.update(&input.event) {
+        Ok(val) => val,
+        Err(err) => {
+            tracing::error!("couldn't process event - details {err}");
+            return None;
+        }
+    };
 
     let output = ProcessEventOutput {
         game_state: state.save(),
-        results
+        results,
+        balances: state.ledger.balances.clone()
     };
 
     Some(serde_wasm_bindgen::to_value(&output)
@@ -70,13 +98,18 @@ struct ProcessEventInput {
     game_state: String,
     simple_rules_str: String,
     compound_rules_str: String,
-    rule_results_str: String
+    rule_results_str: String,
+    #[serde(default)]
+    calendars_str: String,
+    #[serde(default)]
+    start_date: Option<NaiveDate>
 }
 
 #[derive(Serialize, Deserialize)]
 struct ProcessEventOutput {
     game_state: String,
-    results: Vec<(RuleResultKind, Vec<String>)>
+    results: Vec<ResultRecord>,
+    balances: HashMap<String, u64>
 }
 
 fn init_tracing() {
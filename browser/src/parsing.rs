@@ -1,18 +1,23 @@
 use std::error::Error;
+use common::calendar::NamedCalendar;
 use common::compound_rule::NamedCompoundRule;
 use common::rule_result::NamedRuleResult;
 use common::simple_rule::NamedSimpleRule;
 
 pub(super) fn parse_simple_rules(input: &str) -> anyhow::Result<Vec<NamedSimpleRule>> {
-    parse_data(input, |s| parser::parse_simple_rule(s))
+    parse_data(input, parser::parse_simple_rule)
+}
+
+pub(super) fn parse_calendars(input: &str) -> anyhow::Result<Vec<NamedCalendar>> {
+    parse_data(input, parser::parse_calendar)
 }
 
 pub(super) fn parse_compound_rules(input: &str) -> anyhow::Result<Vec<NamedCompoundRule>> {
-    parse_data(input, |s| parser::parse_compound_rule(s))
+    parse_data(input, parser::parse_compound_rule)
 }
 
 pub(super) fn parse_rule_results(input: &str) -> anyhow::Result<Vec<NamedRuleResult>> {
-    parse_data(input, |s| parser::parse_rule_result(s))
+    parse_data(input, parser::parse_rule_result)
 }
 
 fn parse_data<T, E>(input: &str, parsing_fn: impl Fn(&str) -> Result<T, E>) -> anyhow::Result<Vec<T>>
@@ -0,0 +1,33 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::simple_rule::SimpleRulePartValue;
+
+#[derive(Eq, PartialEq)]
+pub struct NamedCalendar {
+    pub name: String,
+    pub calendar: Calendar
+}
+
+impl NamedCalendar {
+    pub fn new(name: String, calendar: Calendar) -> Self {
+        Self { name, calendar }
+    }
+}
+
+// named set of dates, such as exam periods or holidays
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub dates: Vec<SimpleRulePartValue<NaiveDate>>
+}
+
+impl Calendar {
+    pub fn new(dates: Vec<SimpleRulePartValue<NaiveDate>>) -> Self {
+        Self { dates }
+    }
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        self.dates
+            .iter()
+            .any(|d| d.matches(date))
+    }
+}
\ No newline at end of file
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use crate::{Advancing, AdvancingResult};
+use crate::period::{ActiveWindow, RulePeriods};
 
 mod advancing;
 
@@ -21,6 +24,37 @@ pub struct CompoundRule {
     pub every: u32,
     pub seq: CompoundRuleSeq,
     pub values: Vec<String>,
+    // size of the rolling window in days, if progress should be forgotten over time
+    #[serde(default)]
+    pub within: Option<u32>,
+    // completions (name and date) which advanced the rule inside the window
+    #[serde(default)]
+    hits: Vec<(String, NaiveDate)>,
+    // rules which must not complete before this one, for the given amount of days or ever
+    #[serde(default)]
+    pub without: Vec<String>,
+    #[serde(default)]
+    pub without_window: Option<u32>,
+    // last completion date of each of the rules above
+    #[serde(default)]
+    without_hits: HashMap<String, NaiveDate>,
+    // first date seen since the rule started or was reset, as a window can't pass before it
+    #[serde(default)]
+    without_since: Option<NaiveDate>,
+    // longest allowed pause between two steps of a sequence
+    #[serde(default)]
+    pub gap: Option<CompoundRuleGap>,
+    // events seen and date of the last step, while a sequence is in progress
+    #[serde(default)]
+    last_step: Option<(u32, NaiveDate)>,
+    #[serde(flatten)]
+    pub periods: RulePeriods,
+    // dates outside of which the rule isn't available at all
+    #[serde(default)]
+    pub active: Option<ActiveWindow>,
+    // locked rules are unavailable until unlocked by a result
+    #[serde(default)]
+    pub locked: bool,
     // backup used for reset
     og_iterations: i32,
     og_every: u32,
@@ -34,6 +68,17 @@ impl CompoundRule {
             iterations, every,
             seq: seq.clone(),
             values: values.clone(),
+            within: None,
+            hits: Vec::new(),
+            without: Vec::new(),
+            without_window: None,
+            without_hits: HashMap::new(),
+            without_since: None,
+            gap: None,
+            last_step: None,
+            periods: RulePeriods::default(),
+            active: None,
+            locked: false,
             og_iterations: iterations,
             og_every: every,
             og_seq: seq,
@@ -42,6 +87,44 @@ impl CompoundRule {
     }
 }
 
+impl CompoundRule {
+    pub fn excludes(&self, name: &String) -> bool {
+        self.without.contains(name)
+    }
+    // position of the rule among the ones still awaited, if it's awaited at all
+    pub fn awaits(&self, name: &String) -> Option<usize> {
+        self.values.iter().position(|v| v == name)
+    }
+    // streaks and day based windows can't work with undated events
+    pub fn needs_date(&self) -> bool {
+        matches!(self.seq, CompoundRuleSeq::Streak(..) | CompoundRuleSeq::DailyStreak(..))
+            || self.within.is_some()
+            || self.without_window.is_some()
+            || matches!(self.gap, Some(CompoundRuleGap::Days(_)))
+            || self.periods.needs_date()
+            || self.active.is_some()
+    }
+    // resets the rule if the date belongs to a new period, telling whether it did
+    pub fn start_period(&mut self, date: &NaiveDate) -> bool {
+        let started = self.periods.start_period(date);
+        if started {
+            self.iterations = self.og_iterations;
+            self.reset();
+        }
+        started
+    }
+    // drops progress which hasn't been added to for too long
+    pub fn expire(&mut self, date: &NaiveDate) {
+        if self.periods.expire(date) {
+            self.reset();
+        }
+    }
+    // called once per event, before any completions are advanced
+    pub fn tick(&mut self, date: &NaiveDate) {
+        advancing::rule_tick(self, date)
+    }
+}
+
 impl Advancing<(&String, NaiveDate), ()> for CompoundRule {
     fn raw_advance(&mut self, data: &(&String, NaiveDate)) -> AdvancingResult<()> {
         advancing::rule_advance(self, data)
@@ -56,5 +139,16 @@ impl Advancing<(&String, NaiveDate), ()> for CompoundRule {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum CompoundRuleSeq {
-    Any, All, Order, Streak(u32, Vec<NaiveDate>)
+    Any, All, Order, Streak(u32, Vec<NaiveDate>),
+    // amount of distinct referenced rules needed
+    AtLeast(u32),
+    // sequence broken by any other completion in between
+    StrictOrder,
+    // streak length, distinct completions needed each day, streak values and the completions of the current day
+    DailyStreak(u32, u32, Vec<NaiveDate>, Vec<(String, NaiveDate)>)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CompoundRuleGap {
+    Events(u32), Days(u32)
 }
\ No newline at end of file
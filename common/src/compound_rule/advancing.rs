@@ -1,22 +1,34 @@
 use chrono::{Days, NaiveDate};
-use crate::compound_rule::{CompoundRule, CompoundRuleSeq};
+use crate::compound_rule::{CompoundRule, CompoundRuleGap, CompoundRuleSeq};
 use crate::{AdvancingResult, AdvancingResultType};
 
 mod rule;
 
 pub(super) fn rule_advance(rule: &mut CompoundRule, data: &(&String, NaiveDate)) -> AdvancingResult<()> {
-    let adv_res = match rule.seq {
-        CompoundRuleSeq::Any => rule_any_advance(&mut rule.values, &data.0),
-        CompoundRuleSeq::All => rule_all_advance(&mut rule.values, &data.0),
-        CompoundRuleSeq::Order => rule_order_advance(&mut rule.values, &data.0),
-        CompoundRuleSeq::Streak(s_data, ref mut s_values) =>
-            rule_streak_advance(s_values, s_data, &data.1)
-    };
+    rule::watch_without(rule, &data.1);
+    if rule.excludes(data.0) {
+        rule.without_hits.insert(data.0.clone(), data.1);
+        return AdvancingResult::empty();
+    }
+    if rule::is_blocked(rule, &data.1) {
+        return AdvancingResult::empty();
+    }
+    if let Some(window) = rule.within {
+        rule::forget_expired(rule, &data.1, window);
+    }
+    let adv_res = seq_advance(rule, data.0, &data.1);
     match adv_res.res_type {
         AdvancingResultType::None => AdvancingResult::empty(),
         AdvancingResultType::Hit => {
             rule::clean(rule, adv_res.data
                 .expect("CompoundRule's hit guarantees index presence"));
+            if rule.within.is_some() {
+                rule.hits.push((data.0.clone(), data.1));
+            }
+            if rule.gap.is_some() {
+                rule.last_step = Some((0, data.1));
+            }
+            rule.periods.hit(&data.1);
             AdvancingResult::hit(None)
         }
         AdvancingResultType::Restarted => {
@@ -31,12 +43,70 @@ pub(super) fn rule_advance(rule: &mut CompoundRule, data: &(&String, NaiveDate))
             } else {
                 rule.seq = rule.og_seq.clone();
                 rule.values = rule.og_values.clone();
+                rule.hits.clear();
+                rule.last_step = None;
+                rule::forget_without(rule);
+                rule.periods.hit(&data.1);
                 AdvancingResult::hit(None)
             }
         }
     }
 }
 
+fn seq_advance(rule: &mut CompoundRule, name: &String, date: &NaiveDate) -> AdvancingResult<usize> {
+    match rule.seq {
+        CompoundRuleSeq::Any => rule_any_advance(&rule.values, name),
+        CompoundRuleSeq::All => rule_all_advance(&rule.values, name),
+        CompoundRuleSeq::Order => rule_order_advance(&rule.values, name),
+        CompoundRuleSeq::Streak(s_data, ref mut s_values) =>
+            rule_streak_advance(s_values, s_data, date),
+        CompoundRuleSeq::AtLeast(needed) => {
+            let done = rule.og_values.len() - rule.values.len();
+            rule_atleast_advance(&rule.values, name, (needed as usize).saturating_sub(done))
+        }
+        CompoundRuleSeq::DailyStreak(s_data, needed, ref mut s_values, ref mut done) =>
+            rule_daily_streak_advance(&rule.values, s_values, done, (s_data, needed), name, date),
+        CompoundRuleSeq::StrictOrder => {
+            if rule.values.first() != Some(name) {
+                rule::restart_progress(rule);
+            }
+            rule_order_advance(&rule.values, name)
+        }
+    }
+}
+
+pub(super) fn rule_tick(rule: &mut CompoundRule, date: &NaiveDate) {
+    rule::watch_without(rule, date);
+    if let (Some(gap), Some((events, step_date))) = (&rule.gap, &mut rule.last_step) {
+        *events += 1;
+        let exceeded = match gap {
+            CompoundRuleGap::Events(max) => *events > *max,
+            CompoundRuleGap::Days(max) => (*date - *step_date).num_days() > *max as i64
+        };
+        if exceeded {
+            rule::restart_progress(rule);
+        }
+    }
+}
+
+fn rule_daily_streak_advance(values: &[String], streak_values: &mut Vec<NaiveDate>,
+                             done: &mut Vec<(String, NaiveDate)>, (streak_data, needed): (u32, u32),
+                             name: &String, date: &NaiveDate) -> AdvancingResult<usize> {
+    // progress of a single day doesn't carry over to the next one
+    if done.iter().any(|(_, d)| d != date) {
+        done.clear();
+    }
+    if !values.contains(name) || done.iter().any(|(n, _)| n == name) {
+        return AdvancingResult::empty();
+    }
+    done.push((name.clone(), *date));
+    if done.len() == needed as usize {
+        rule_streak_advance(streak_values, streak_data, date)
+    } else {
+        AdvancingResult::empty()
+    }
+}
+
 fn rule_streak_advance(values: &mut Vec<NaiveDate>, streak_data: u32, data: &NaiveDate)
     -> AdvancingResult<usize> {
     if values.is_empty() {
@@ -76,7 +146,26 @@ fn rule_streak_advance(values: &mut Vec<NaiveDate>, streak_data: u32, data: &Nai
     }
 }
 
-fn rule_order_advance(values: &mut Vec<String>, data: &String) -> AdvancingResult<usize> {
+fn rule_atleast_advance(values: &[String], data: &String, remaining: usize) -> AdvancingResult<usize> {
+    if remaining == 0 {
+        AdvancingResult::completed(None)
+    } else {
+        let found = values
+            .iter()
+            .position(|v| v.eq(data));
+        if found.is_some() {
+            if remaining == 1 {
+                AdvancingResult::completed(found)
+            } else {
+                AdvancingResult::hit(found)
+            }
+        } else {
+            AdvancingResult::empty()
+        }
+    }
+}
+
+fn rule_order_advance(values: &[String], data: &String) -> AdvancingResult<usize> {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -95,7 +184,7 @@ fn rule_order_advance(values: &mut Vec<String>, data: &String) -> AdvancingResul
     }
 }
 
-fn rule_all_advance(values: &mut Vec<String>, data: &String) -> AdvancingResult<usize> {
+fn rule_all_advance(values: &[String], data: &String) -> AdvancingResult<usize> {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -114,7 +203,7 @@ fn rule_all_advance(values: &mut Vec<String>, data: &String) -> AdvancingResult<
     }
 }
 
-fn rule_any_advance(values: &mut Vec<String>, data: &String) -> AdvancingResult<usize> {
+fn rule_any_advance(values: &[String], data: &String) -> AdvancingResult<usize> {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -133,6 +222,10 @@ pub(super) fn rule_reset(rule: &mut CompoundRule) {
     rule.every = rule.og_every;
     rule.seq = rule.og_seq.clone();
     rule.values = rule.og_values.clone();
+    rule.hits.clear();
+    rule.last_step = None;
+    rule.without_since = None;
+    rule::forget_without(rule);
 }
 
 pub(super) fn rule_needs_reset(iterations: i32) -> bool {
@@ -1,9 +1,68 @@
+use chrono::NaiveDate;
+use crate::AdvancingResultType;
 use crate::compound_rule::{CompoundRule, CompoundRuleSeq};
 
 pub(super) fn clean(rule: &mut CompoundRule, index: usize) {
-    if let CompoundRuleSeq::Streak(_, ref mut values) = rule.seq {
+    if let CompoundRuleSeq::Streak(_, ref mut values) | CompoundRuleSeq::DailyStreak(_, _, ref mut values, _) = rule.seq {
         values.remove(index);
     } else {
         rule.values.remove(index);
     }
+}
+
+// drops completions outside of the window and replays the rest from scratch
+pub(super) fn forget_expired(rule: &mut CompoundRule, date: &NaiveDate, window: u32) {
+    let expired = rule.hits
+        .iter()
+        .any(|(_, d)| (*date - *d).num_days() >= window as i64);
+    if expired {
+        let kept: Vec<_> = std::mem::take(&mut rule.hits)
+            .into_iter()
+            .filter(|(_, d)| (*date - *d).num_days() < window as i64)
+            .collect();
+        rule.seq = rule.og_seq.clone();
+        rule.values = rule.og_values.clone();
+        for (name, d) in kept {
+            let adv_res = super::seq_advance(rule, &name, &d);
+            if let (AdvancingResultType::Hit, Some(index)) = (adv_res.res_type, adv_res.data) {
+                clean(rule, index);
+                rule.hits.push((name, d));
+            }
+        }
+    }
+}
+
+// a windowed absence is measured from the first date seen since the start or the last reset
+pub(super) fn watch_without(rule: &mut CompoundRule, date: &NaiveDate) {
+    if rule.without_window.is_some() && rule.without_since.is_none() {
+        rule.without_since = Some(*date);
+    }
+}
+
+// an excluded completion blocks the rule until it drops out of the window, or for good without one
+pub(super) fn is_blocked(rule: &mut CompoundRule, date: &NaiveDate) -> bool {
+    if let Some(window) = rule.without_window {
+        rule.without_hits
+            .retain(|_, d| (*date - *d).num_days() < window as i64);
+        // the whole window has to be watched before the absence counts
+        if rule.without_since.is_some_and(|d| (*date - d).num_days() < window as i64) {
+            return true;
+        }
+    }
+    !rule.without_hits.is_empty()
+}
+
+// windowed absence is about time rather than progress, so it outlives resets
+pub(super) fn forget_without(rule: &mut CompoundRule) {
+    if rule.without_window.is_none() {
+        rule.without_hits.clear();
+    }
+}
+
+// drops the progress of the current cycle, keeping the cycles counted by every
+pub(super) fn restart_progress(rule: &mut CompoundRule) {
+    rule.seq = rule.og_seq.clone();
+    rule.values = rule.og_values.clone();
+    rule.hits.clear();
+    rule.last_step = None;
 }
\ No newline at end of file
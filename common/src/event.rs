@@ -1,19 +1,133 @@
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use chrono::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Event {
+    #[serde(default)]
+    pub category: Option<String>,
     pub parts: HashMap<String, EventPartValue>
 }
 
 impl Event {
     pub fn new(parts: HashMap<String, EventPartValue>) -> Self {
-        Self { parts }
+        Self { category: None, parts }
+    }
+    pub fn categorized(category: String, parts: HashMap<String, EventPartValue>) -> Self {
+        Self { category: Some(category), parts }
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum EventPartValue {
-    DateBased(NaiveDate), NumberBased(u32), TimeBased(NaiveTime)
+    DateBased(NaiveDate), NumberBased(u32), TimeBased(NaiveTime), DurationBased(Duration), List(Vec<ListItem>)
+}
+
+impl Display for EventPartValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EventPartValue::DateBased(val) => Display::fmt(val, f),
+            EventPartValue::NumberBased(val) => Display::fmt(val, f),
+            EventPartValue::TimeBased(val) => Display::fmt(val, f),
+            EventPartValue::DurationBased(val) => Display::fmt(val, f),
+            EventPartValue::List(vals) => {
+                let joined: Vec<_> = vals.iter().map(ToString::to_string).collect();
+                f.write_str(&joined.join(","))
+            }
+        }
+    }
+}
+
+// amount of seconds, written in rules with units such as 90s, 5m or 1h30m
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Duration(pub u32);
+
+#[derive(Debug)]
+pub struct DurationParseError;
+
+impl FromStr for Duration {
+    type Err = DurationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // bare numbers are seconds
+        if let Ok(secs) = s.parse::<u32>() {
+            return Ok(Duration(secs));
+        }
+        let mut secs: u32 = 0;
+        let mut rest = s;
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or(DurationParseError)?;
+            let amount = rest[..digits].parse::<u32>().map_err(|_| DurationParseError)?;
+            let unit = match rest.as_bytes()[digits] {
+                b'd' => 86400,
+                b'h' => 3600,
+                b'm' => 60,
+                b's' => 1,
+                _ => return Err(DurationParseError)
+            };
+            secs = amount.checked_mul(unit)
+                .and_then(|v| v.checked_add(secs))
+                .ok_or(DurationParseError)?;
+            // units are single ascii letters
+            rest = &rest[digits + 1..];
+        }
+        if s.is_empty() {
+            Err(DurationParseError)
+        } else {
+            Ok(Duration(secs))
+        }
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+// single element of a list part, such as a tag or the number of a passed test
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(untagged, from = "RawListItem")]
+pub enum ListItem {
+    Number(u32), Text(String)
+}
+
+// numeric strings are read as numbers, the same way rules are parsed, so "3" and 3 are the same item
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawListItem {
+    Number(u32), Text(String)
+}
+
+impl From<RawListItem> for ListItem {
+    fn from(value: RawListItem) -> Self {
+        match value {
+            RawListItem::Number(val) => ListItem::Number(val),
+            RawListItem::Text(val) => val.parse::<u32>()
+                .map_or(ListItem::Text(val), ListItem::Number)
+        }
+    }
+}
+
+impl FromStr for ListItem {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse::<u32>()
+            .map_or_else(|_| ListItem::Text(s.to_owned()), ListItem::Number))
+    }
+}
+
+impl Display for ListItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ListItem::Number(val) => Display::fmt(val, f),
+            ListItem::Text(val) => Display::fmt(val, f)
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
+pub mod calendar;
 pub mod event;
+pub mod period;
 pub mod simple_rule;
 pub mod compound_rule;
 pub mod rule_result;
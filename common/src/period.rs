@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+// calendar period after which a rule starts over
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ResetPeriod {
+    Daily, Weekly(Weekday), Monthly
+}
+
+impl ResetPeriod {
+    // first day of the period the date belongs to
+    pub fn start(&self, date: &NaiveDate) -> NaiveDate {
+        match self {
+            ResetPeriod::Daily => *date,
+            ResetPeriod::Weekly(first_day) => date.week(*first_day).first_day(),
+            ResetPeriod::Monthly => date.with_day(1)
+                .expect("first day exists in every month")
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ResetPeriodParseError;
+
+impl FromStr for ResetPeriod {
+    type Err = ResetPeriodParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "daily" => Ok(ResetPeriod::Daily),
+            "weekly" => Ok(ResetPeriod::Weekly(Weekday::Mon)),
+            "monthly" => Ok(ResetPeriod::Monthly),
+            _ => {
+                let day = s.strip_prefix("weekly(")
+                    .and_then(|v| v.strip_suffix(')'))
+                    .ok_or(ResetPeriodParseError)?;
+                day.parse()
+                    .map(ResetPeriod::Weekly)
+                    .map_err(|_| ResetPeriodParseError)
+            }
+        }
+    }
+}
+
+// reset period and expiry shared by simple and compound rules
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RulePeriods {
+    // period after which progress and completion are reset, along with the start of the current one
+    #[serde(default)]
+    pub resets: Option<ResetPeriod>,
+    #[serde(default)]
+    period_start: Option<NaiveDate>,
+    // days without a hit after which progress is dropped, along with the date of the last hit
+    #[serde(default)]
+    pub expires: Option<u32>,
+    #[serde(default)]
+    last_hit: Option<NaiveDate>
+}
+
+impl RulePeriods {
+    pub fn needs_date(&self) -> bool {
+        self.resets.is_some() || self.expires.is_some()
+    }
+    // moves to the period the date belongs to, telling whether an earlier one has ended
+    pub(crate) fn start_period(&mut self, date: &NaiveDate) -> bool {
+        let Some(period) = &self.resets else {
+            return false;
+        };
+        let start = period.start(date);
+        match self.period_start {
+            // late events of an earlier period don't move it back
+            Some(current) if current >= start => false,
+            current => {
+                self.period_start = Some(start);
+                current.is_some()
+            }
+        }
+    }
+    // forgets the last hit once it's too old, telling whether progress should be dropped
+    pub(crate) fn expire(&mut self, date: &NaiveDate) -> bool {
+        let (Some(days), Some(last_hit)) = (self.expires, self.last_hit) else {
+            return false;
+        };
+        // expiries past the last representable date never come
+        let expired = last_hit.checked_add_days(Days::new(days.into()))
+            .is_some_and(|end| end < *date);
+        if expired {
+            self.last_hit = None;
+        }
+        expired
+    }
+    pub(crate) fn hit(&mut self, date: &NaiveDate) {
+        if self.expires.is_some() {
+            self.last_hit = Some(*date);
+        }
+    }
+}
+
+// dates between which a rule is available, both inclusive
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ActiveWindow {
+    pub from: NaiveDate,
+    pub to: NaiveDate
+}
+
+impl ActiveWindow {
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Self {
+        Self { from, to }
+    }
+    pub fn starts_after(&self, date: &NaiveDate) -> bool {
+        *date < self.from
+    }
+    pub fn ended_before(&self, date: &NaiveDate) -> bool {
+        *date > self.to
+    }
+}
+
+#[derive(Debug)]
+pub struct ActiveWindowParseError;
+
+impl FromStr for ActiveWindow {
+    type Err = ActiveWindowParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |raw: &str| raw.replace('.', "-")
+            .parse::<NaiveDate>()
+            .map_err(|_| ActiveWindowParseError);
+        let (from, to) = s.split_once("..").ok_or(ActiveWindowParseError)?;
+        let (from, to) = (parse(from)?, parse(to)?);
+        if from <= to {
+            Ok(Self::new(from, to))
+        } else {
+            Err(ActiveWindowParseError)
+        }
+    }
+}
\ No newline at end of file
@@ -1,3 +1,4 @@
+use chrono::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 use crate::{Advancing, AdvancingResult};
 
@@ -24,6 +25,10 @@ impl RuleResult {
     pub fn new(values: Vec<RuleResultValue>) -> Self {
         Self { values }
     }
+    // brings back the original arguments and iterations of every value
+    pub fn restore(&mut self) {
+        advancing::result_restore(&mut self.values)
+    }
 }
 
 impl Advancing<(), Vec<(RuleResultKind, Vec<String>)>> for RuleResult {
@@ -45,12 +50,14 @@ pub struct RuleResultValue {
     pub seq: RuleResultSeq,
     pub values: Vec<String>,
     // backup used for reset
-    og_values: Vec<String>
+    og_values: Vec<String>,
+    #[serde(default)]
+    og_iterations: i32
 }
 
 impl RuleResultValue {
     pub fn new(iterations: i32, kind: RuleResultKind, seq: RuleResultSeq, values: Vec<String>) -> Self {
-        Self { iterations, kind, seq, values: values.clone(), og_values: values }
+        Self { iterations, kind, seq, values: values.clone(), og_values: values, og_iterations: iterations }
     }
 }
 
@@ -73,5 +80,23 @@ pub enum RuleResultSeq {
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum RuleResultKind {
-    Message, Offer, Open, Restart, Reward
+    Message, Offer, Open, Restart, Reward, Unlock, Lock, Disable, Reset
+}
+
+// result given out by the engine, along with what caused it
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResultRecord {
+    pub kind: RuleResultKind,
+    pub args: Vec<String>,
+    pub rule: String,
+    pub rule_type: RuleType,
+    // completions of the rule, including the one which caused the result
+    pub completions: u32,
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum RuleType {
+    Simple, Compound
 }
\ No newline at end of file
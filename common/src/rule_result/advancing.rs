@@ -3,7 +3,7 @@ use rand::{Rng, thread_rng};
 use crate::{Advancing, AdvancingResult, AdvancingResultType};
 use crate::rule_result::{RuleResultKind, RuleResultSeq, RuleResultValue};
 
-pub(super) fn result_advance(values: &mut Vec<RuleResultValue>) -> AdvancingResult<Vec<(RuleResultKind, Vec<String>)>> {
+pub(super) fn result_advance(values: &mut [RuleResultValue]) -> AdvancingResult<Vec<(RuleResultKind, Vec<String>)>> {
     let adv_res: Vec<_> = values
         .iter_mut()
         .map(|v| v.advance(&()))
@@ -25,18 +25,31 @@ pub(super) fn result_advance(values: &mut Vec<RuleResultValue>) -> AdvancingResu
 pub(super) fn result_reset() {
 }
 
+pub(super) fn result_restore(values: &mut [RuleResultValue]) {
+    values
+        .iter_mut()
+        .for_each(|v| {
+            v.iterations = v.og_iterations;
+            value_reset(v);
+        })
+}
+
 pub(super) fn result_needs_reset() -> bool {
     false
 }
 
 pub(super) fn value_advance(value: &mut RuleResultValue) -> AdvancingResult<(RuleResultKind, Vec<String>)> {
+    // values which ran out of repeats stay done while others of the result keep going
+    if value.iterations == 0 {
+        return AdvancingResult::completed(None);
+    }
     let arg_amount = decide_vals_amount(&value.kind);
     let args = match value.seq {
         RuleResultSeq::All => value_all_advance(&mut value.values),
         RuleResultSeq::Order => value_order_advance(&mut value.values, arg_amount),
-        RuleResultSeq::Random => value_random_advance(&mut value.values, arg_amount),
+        RuleResultSeq::Random => value_random_advance(&value.values, arg_amount),
         RuleResultSeq::RandomOnce => value_random_once_advance(&mut value.values, arg_amount),
-        RuleResultSeq::Choice => value_choice_advance(&mut value.values)
+        RuleResultSeq::Choice => value_choice_advance(&value.values)
     };
     let data = Some((value.kind.clone(), args));
     let completed = match value.seq {
@@ -44,14 +57,18 @@ pub(super) fn value_advance(value: &mut RuleResultValue) -> AdvancingResult<(Rul
         RuleResultSeq::Order | RuleResultSeq::RandomOnce => value.values.is_empty()
     };
     if completed {
+        // -1 repeats forever, anything above counts down to 0
+        if value.iterations > 0 {
+            value.iterations -= 1;
+        }
         AdvancingResult::completed(data)
     } else {
         AdvancingResult::hit(data)
     }
 }
 
-fn value_choice_advance(values: &mut Vec<String>) -> Vec<String> {
-    values.clone()
+fn value_choice_advance(values: &[String]) -> Vec<String> {
+    values.to_vec()
 }
 
 fn value_random_once_advance(values: &mut Vec<String>, arg_amount: usize) -> Vec<String> {
@@ -60,11 +77,8 @@ fn value_random_once_advance(values: &mut Vec<String>, arg_amount: usize) -> Vec
         .collect()
 }
 
-fn value_random_advance(values: &mut Vec<String>, arg_amount: usize) -> Vec<String> {
-    values[random_args_range(arg_amount, values.len())]
-        .iter()
-        .cloned()
-        .collect()
+fn value_random_advance(values: &[String], arg_amount: usize) -> Vec<String> {
+    values[random_args_range(arg_amount, values.len())].to_vec()
 }
 
 fn random_args_range(arg_amount: usize, vals_size: usize) -> Range<usize> {
@@ -81,9 +95,7 @@ fn value_order_advance(values: &mut Vec<String>, arg_amount: usize) -> Vec<Strin
 }
 
 fn value_all_advance(values: &mut Vec<String>) -> Vec<String> {
-    values
-        .drain(..)
-        .collect()
+    std::mem::take(values)
 }
 
 fn decide_vals_amount(kind: &RuleResultKind) -> usize {
@@ -99,5 +111,5 @@ pub(super) fn value_reset(value: &mut RuleResultValue) {
 }
 
 pub(super) fn value_needs_reset(iterations: i32) -> bool {
-    iterations == -1
+    iterations != 0
 }
\ No newline at end of file
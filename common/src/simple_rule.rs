@@ -1,11 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug};
+use std::str::FromStr;
 
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{Datelike, NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
 use crate::{Advancing, AdvancingResult};
-use crate::event::Event;
+use crate::calendar::Calendar;
+use crate::event::{Duration, Event, ListItem};
+use crate::period::{ActiveWindow, RulePeriods};
 
 mod advancing;
 
@@ -24,12 +27,94 @@ impl NamedSimpleRule {
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct SimpleRule {
     pub iterations: i32,
-    pub parts: HashMap<String, WrappedSimpleRulePart>
+    // categorized rules only see events of the same category
+    #[serde(default)]
+    pub category: Option<String>,
+    pub parts: HashMap<String, WrappedSimpleRulePart>,
+    // strict rules require every part that isn't optional to be present in the event
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub optional: HashSet<String>,
+    // values remembered by history parts (part name -> group -> value), kept across resets
+    #[serde(default)]
+    pub memory: HashMap<String, HashMap<String, u32>>,
+    #[serde(flatten)]
+    pub periods: RulePeriods,
+    // dates outside of which the rule isn't available at all
+    #[serde(default)]
+    pub active: Option<ActiveWindow>,
+    // locked rules are unavailable until unlocked by a result
+    #[serde(default)]
+    pub locked: bool,
+    // backup used for period resets
+    #[serde(default)]
+    og_iterations: i32
 }
 
 impl SimpleRule {
     pub fn new(iterations: i32, parts: HashMap<String, WrappedSimpleRulePart>) -> Self {
-        Self { iterations, parts }
+        Self {
+            iterations, category: None, parts,
+            strict: false,
+            optional: HashSet::new(),
+            memory: HashMap::new(),
+            periods: RulePeriods::default(),
+            active: None,
+            locked: false,
+            og_iterations: iterations
+        }
+    }
+    pub fn handles(&self, event: &Event) -> bool {
+        self.category.is_none() || self.category == event.category
+    }
+    pub fn needs_date(&self) -> bool {
+        self.periods.needs_date() || self.active.is_some()
+    }
+    // resets the rule if the date belongs to a new period, telling whether it did
+    pub fn start_period(&mut self, date: &NaiveDate) -> bool {
+        let started = self.periods.start_period(date);
+        if started {
+            self.iterations = self.og_iterations;
+            self.reset();
+        }
+        started
+    }
+    // drops progress which hasn't been added to for too long
+    pub fn expire(&mut self, date: &NaiveDate) {
+        if self.periods.expire(date) {
+            self.reset();
+        }
+    }
+    // copies referenced calendars into date parts, failing with the name of an unknown one
+    pub fn link_calendars(&mut self, calendars: &HashMap<String, Calendar>) -> Result<(), String> {
+        for part in self.parts.values_mut() {
+            if let WrappedSimpleRulePart::Date(ref mut p) = part {
+                match p.seq {
+                    SimpleRuleSeq::In(ref name, ref mut calendar)
+                    | SimpleRuleSeq::NotIn(ref name, ref mut calendar)
+                    | SimpleRuleSeq::SkippingStreak(_, ref name, ref mut calendar) => {
+                        *calendar = Some(calendars.get(name)
+                            .ok_or_else(|| name.clone())?
+                            .clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+    // name of a referenced calendar which hasn't been linked yet
+    pub fn unlinked_calendar(&self) -> Option<&String> {
+        self.parts.values().find_map(|part| match part {
+            WrappedSimpleRulePart::Date(p) => match &p.seq {
+                SimpleRuleSeq::In(name, None)
+                | SimpleRuleSeq::NotIn(name, None)
+                | SimpleRuleSeq::SkippingStreak(_, name, None) => Some(name),
+                _ => None
+            },
+            _ => None
+        })
     }
 }
 
@@ -49,7 +134,21 @@ impl Advancing<Event, ()> for SimpleRule {
 pub enum WrappedSimpleRulePart {
     Number(SimpleRulePart<u32>),
     Time(SimpleRulePart<NaiveTime>),
-    Date(SimpleRulePart<NaiveDate>)
+    Date(SimpleRulePart<NaiveDate>),
+    Duration(SimpleRulePart<Duration>),
+    List(SimpleRulePart<ListItem>)
+}
+
+impl WrappedSimpleRulePart {
+    fn seq(&self) -> &SimpleRuleSeq {
+        match self {
+            WrappedSimpleRulePart::Number(p) => &p.seq,
+            WrappedSimpleRulePart::Time(p) => &p.seq,
+            WrappedSimpleRulePart::Date(p) => &p.seq,
+            WrappedSimpleRulePart::Duration(p) => &p.seq,
+            WrappedSimpleRulePart::List(p) => &p.seq
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -71,7 +170,7 @@ impl<T> SimpleRulePart<T> where T: Clone {
 
 impl Advancing<u32, usize> for SimpleRulePart<u32> {
     fn raw_advance(&mut self, data: &u32) -> AdvancingResult<usize> {
-        advancing::part_universal_advance(self, data)
+        advancing::part_number_advance(self, data)
     }
     fn reset(&mut self) {
         advancing::part_reset(self)
@@ -105,6 +204,30 @@ impl Advancing<NaiveDate, usize> for SimpleRulePart<NaiveDate> {
     }
 }
 
+impl Advancing<Duration, usize> for SimpleRulePart<Duration> {
+    fn raw_advance(&mut self, data: &Duration) -> AdvancingResult<usize> {
+        advancing::part_universal_advance(self, data)
+    }
+    fn reset(&mut self) {
+        advancing::part_reset(self)
+    }
+    fn needs_reset(&self) -> bool {
+        advancing::part_needs_reset()
+    }
+}
+
+impl Advancing<Vec<ListItem>, usize> for SimpleRulePart<ListItem> {
+    fn raw_advance(&mut self, data: &Vec<ListItem>) -> AdvancingResult<usize> {
+        advancing::part_list_advance(self, data)
+    }
+    fn reset(&mut self) {
+        advancing::part_reset(self)
+    }
+    fn needs_reset(&self) -> bool {
+        advancing::part_needs_reset()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SimpleRulePartValue<T> where T: Clone {
     border: SimpleRuleBorder,
@@ -121,26 +244,179 @@ impl<T> SimpleRulePartValue<T> where T: Clone {
     }
 }
 
-impl<T> SimpleRulePartValue<T> where T: PartialEq + Eq + PartialOrd + Ord + Clone {
-    fn matches(&self, data: &T) -> bool {
+impl<T> SimpleRulePartValue<T> where T: Clone {
+    pub(crate) fn matches(&self, data: &T) -> bool where T: PartValue {
+        T::matches(self, data)
+    }
+}
+
+impl<T> SimpleRulePartValue<T> where T: Ord + Clone {
+    // borders shared by every kind of value, steps and patterns are built for numbers and dates only
+    fn matches_ordered(&self, data: &T) -> bool {
         let l_v = &self.left_val;
         match self.border {
             SimpleRuleBorder::Exact => data.eq(l_v),
             SimpleRuleBorder::Less => data.lt(l_v),
             SimpleRuleBorder::LessEq => data.le(l_v),
             SimpleRuleBorder::Greater => data.gt(l_v),
-            SimpleRuleBorder::GreaterEq => data.gt(l_v),
-            SimpleRuleBorder::Between => data.ge(l_v) && data.le(&self.right_val.as_ref().unwrap())
+            SimpleRuleBorder::GreaterEq => data.ge(l_v),
+            SimpleRuleBorder::Between => data.ge(l_v) && data.le(self.right_val.as_ref().unwrap()),
+            SimpleRuleBorder::Modulo | SimpleRuleBorder::Recurring(..) => false
+        }
+    }
+}
+
+// list sizes are numbers, which are compared against list items
+impl From<SimpleRulePartValue<u32>> for SimpleRulePartValue<ListItem> {
+    fn from(value: SimpleRulePartValue<u32>) -> Self {
+        Self::new(value.border, ListItem::Number(value.left_val), value.right_val.map(ListItem::Number))
+    }
+}
+
+impl SimpleRulePartValue<NaiveDate> {
+    pub fn recurring(from: DatePattern, to: Option<DatePattern>) -> Self {
+        Self::new(SimpleRuleBorder::Recurring(from, to), NaiveDate::default(), None)
+    }
+}
+
+// values of rule parts, numbers and dates add their own borders to the ordered ones
+pub(crate) trait PartValue: Ord + Clone {
+    fn matches(value: &SimpleRulePartValue<Self>, data: &Self) -> bool {
+        value.matches_ordered(data)
+    }
+}
+
+impl PartValue for NaiveTime {}
+
+impl PartValue for Duration {}
+
+impl PartValue for ListItem {}
+
+impl PartValue for u32 {
+    fn matches(value: &SimpleRulePartValue<Self>, data: &Self) -> bool {
+        match (&value.border, value.right_val) {
+            (SimpleRuleBorder::Modulo, Some(offset)) => value.left_val != 0 && data % value.left_val == offset,
+            _ => value.matches_ordered(data)
+        }
+    }
+}
+
+impl PartValue for NaiveDate {
+    fn matches(value: &SimpleRulePartValue<Self>, data: &Self) -> bool {
+        match &value.border {
+            SimpleRuleBorder::Recurring(from, None) => from.year.is_none_or(|y| y == data.year())
+                && from.month.is_none_or(|m| m == data.month())
+                && from.day.is_none_or(|d| d == data.day()),
+            SimpleRuleBorder::Recurring(from, Some(to)) => {
+                let (left, right, key) = (from.key(), to.key(), from.key_of(data));
+                if left <= right {
+                    left <= key && key <= right
+                } else {
+                    // ranges such as *.12.20..*.01.06 wrap around the end of the year
+                    key >= left || key <= right
+                }
+            }
+            _ => value.matches_ordered(data)
+        }
+    }
+}
+
+// date with wildcard components, e.g. *.12.24 or *.*.01
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DatePattern {
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>
+}
+
+impl DatePattern {
+    // ranges compare the concrete month and day, so both ends need the same shape without a year
+    pub fn ranges_with(&self, other: &DatePattern) -> bool {
+        self.year.is_none() && other.year.is_none()
+            && self.day.is_some() && other.day.is_some()
+            && self.month.is_some() == other.month.is_some()
+    }
+    fn key(&self) -> (u32, u32) {
+        (self.month.unwrap_or(0), self.day.unwrap_or(0))
+    }
+    fn key_of(&self, date: &NaiveDate) -> (u32, u32) {
+        (self.month.map_or(0, |_| date.month()), date.day())
+    }
+}
+
+#[derive(Debug)]
+pub struct DatePatternParseError;
+
+impl FromStr for DatePattern {
+    type Err = DatePatternParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split('.');
+        let mut next_field = |max: u32| -> Result<Option<u32>, DatePatternParseError> {
+            match fields.next().ok_or(DatePatternParseError)? {
+                "*" => Ok(None),
+                raw_val => match raw_val.parse::<u32>() {
+                    Ok(val) if (1..=max).contains(&val) => Ok(Some(val)),
+                    _ => Err(DatePatternParseError)
+                }
+            }
+        };
+        let year = next_field(u32::MAX)?;
+        let month = next_field(12)?;
+        let day = next_field(31)?;
+        if fields.next().is_some() || (year.is_some() && month.is_some() && day.is_some()) {
+            return Err(DatePatternParseError);
+        }
+        let year = year
+            .map(i32::try_from)
+            .transpose()
+            .map_err(|_| DatePatternParseError)?;
+        Ok(Self { year, month, day })
+    }
+}
+
+impl SimpleRulePartValue<u32> {
+    // statistics such as an average don't have to be whole numbers
+    fn matches_stat(&self, data: f64) -> bool {
+        let l_v = self.left_val as f64;
+        match self.border {
+            SimpleRuleBorder::Exact => data == l_v,
+            SimpleRuleBorder::Less => data < l_v,
+            SimpleRuleBorder::LessEq => data <= l_v,
+            SimpleRuleBorder::Greater => data > l_v,
+            SimpleRuleBorder::GreaterEq => data >= l_v,
+            SimpleRuleBorder::Between => data >= l_v && data <= self.right_val.unwrap() as f64,
+            SimpleRuleBorder::Modulo => data.fract() == 0.0 && u32::matches(self, &(data as u32)),
+            SimpleRuleBorder::Recurring(..) => false
         }
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SimpleRuleSeq {
-    Any, All, Order, Streak(u32), Selected(u8)
+    Any, All, Order, Streak(u32), Selected(u8),
+    // stat kind, window size and the buffer of the most recent values
+    Stat(SimpleRuleStat, u32, Vec<u32>),
+    // history comparisons with an optional grouping part
+    Best(Option<String>), Improved(Option<String>),
+    // every nth occurrence and the amount of occurrences seen so far
+    Nth(u32, u32),
+    // list membership and list size checks
+    ContainsAll, ContainsAny, Size,
+    // calendar membership, with the calendar copied in once linked
+    In(String, Option<Calendar>), NotIn(String, Option<Calendar>),
+    // streak which skips the days of a calendar
+    SkippingStreak(u32, String, Option<Calendar>)
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SimpleRuleStat {
+    Avg, Max, Min, Median
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SimpleRuleBorder {
-    Exact, Less, LessEq, Greater, GreaterEq, Between
+    Exact, Less, LessEq, Greater, GreaterEq, Between, Modulo,
+    // date patterns, with the end of the range if there is one
+    Recurring(DatePattern, Option<DatePattern>)
 }
\ No newline at end of file
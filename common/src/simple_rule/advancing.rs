@@ -1,16 +1,28 @@
+use std::collections::HashMap;
+
 use chrono::{Days, NaiveDate};
 use chrono::Datelike;
 
 use crate::{Advancing, AdvancingResult};
-use crate::event::Event;
-use crate::simple_rule::{SimpleRule, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, WrappedSimpleRulePart};
+use crate::calendar::Calendar;
+use crate::event::{Event, ListItem};
+use crate::simple_rule::{SimpleRule, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, SimpleRuleStat, PartValue, WrappedSimpleRulePart};
 
 mod rule;
 
 pub(super) fn rule_advance(rule: &mut SimpleRule, data: &Event) -> AdvancingResult<()> {
-    let parts_adv_res: Vec<_> = data.parts
+    if rule.strict && !rule::required_parts_present(rule, data) {
+        return AdvancingResult::empty();
+    }
+    // stateful parts remember every value they see, so they go last
+    // to only record events matching the rest of the rule
+    let (stateless, stateful): (Vec<_>, Vec<_>) = data.parts
         .iter()
-        .map_while(|(k, v)| rule::advance_part(&mut rule.parts, k, v))
+        .partition(|(k, _)| !rule::is_stateful(&rule.parts, k));
+    let parts_adv_res: Vec<_> = stateless
+        .into_iter()
+        .chain(stateful)
+        .map_while(|(k, v)| rule::advance_part(rule, data, k, v))
         .collect();
     if parts_adv_res.len() == data.parts.len() {
         if rule::all_parts_completed(&parts_adv_res) {
@@ -18,6 +30,7 @@ pub(super) fn rule_advance(rule: &mut SimpleRule, data: &Event) -> AdvancingResu
             AdvancingResult::completed(None)
         } else {
             rule::clean_parts(rule, &parts_adv_res);
+            rule::record_hit(rule, data);
             AdvancingResult::hit(None)
         }
     } else {
@@ -34,17 +47,28 @@ pub(super) fn rule_reset(rule: &mut SimpleRule) {
             WrappedSimpleRulePart::Time(ref mut p) =>
                 p.reset(),
             WrappedSimpleRulePart::Date(ref mut p) =>
+                p.reset(),
+            WrappedSimpleRulePart::Duration(ref mut p) =>
+                p.reset(),
+            WrappedSimpleRulePart::List(ref mut p) =>
                 p.reset()
         });
 }
 
 pub(super) fn part_date_advance(part: &mut SimpleRulePart<NaiveDate>, data: &NaiveDate) -> AdvancingResult<usize> {
     match part.seq {
-        SimpleRuleSeq::Any => part_universal_any_advance(&mut part.values, data),
-        SimpleRuleSeq::All => part_universal_all_advance(&mut part.values, data),
-        SimpleRuleSeq::Order => part_universal_order_advance(&mut part.values, data),
-        SimpleRuleSeq::Streak(streak_val) => part_date_streak_advance(&mut part.values, data, streak_val),
-        SimpleRuleSeq::Selected(day_val) => part_date_selected_advance(data, day_val)
+        SimpleRuleSeq::Any => part_universal_any_advance(&part.values, data),
+        SimpleRuleSeq::All => part_universal_all_advance(&part.values, data),
+        SimpleRuleSeq::Order => part_universal_order_advance(&part.values, data),
+        SimpleRuleSeq::Streak(streak_val) =>
+            part_date_streak_advance(&mut part.values, data, streak_val, None),
+        SimpleRuleSeq::SkippingStreak(streak_val, _, ref skipped) =>
+            part_date_streak_advance(&mut part.values, data, streak_val, skipped.as_ref()),
+        SimpleRuleSeq::In(_, ref calendar) => part_date_calendar_advance(calendar.as_ref(), data, true),
+        SimpleRuleSeq::NotIn(_, ref calendar) => part_date_calendar_advance(calendar.as_ref(), data, false),
+        SimpleRuleSeq::Selected(day_val) => part_date_selected_advance(data, day_val),
+        SimpleRuleSeq::Nth(nth, ref mut seen) => part_universal_nth_advance(&part.values, data, nth, seen),
+        _ => panic!("unsupported seq for this SimpleRulePart")
     }
 }
 
@@ -61,8 +85,26 @@ fn part_date_selected_advance(data: &NaiveDate, day_val: u8) -> AdvancingResult<
     }
 }
 
+// an unlinked calendar matches neither way, State::update refuses to run with one anyway
+fn part_date_calendar_advance(calendar: Option<&Calendar>, data: &NaiveDate, inside: bool) -> AdvancingResult<usize> {
+    match calendar {
+        Some(calendar) if calendar.contains(data) == inside => AdvancingResult::completed(None),
+        _ => AdvancingResult::empty()
+    }
+}
+
+// day after the given one, jumping over skipped days (within a year, so full calendars don't loop)
+fn next_streak_day(data: &NaiveDate, skipped: Option<&Calendar>) -> NaiveDate {
+    let next = |days| data.checked_add_days(Days::new(days))
+        .expect("real date won't suffer from adding a day");
+    (1..=366)
+        .map(next)
+        .find(|day| !skipped.is_some_and(|c| c.contains(day)))
+        .unwrap_or_else(|| next(1))
+}
+
 fn part_date_streak_advance(values: &mut Vec<SimpleRulePartValue<NaiveDate>>,
-                            data: &NaiveDate, streak_val: u32) -> AdvancingResult<usize> {
+                            data: &NaiveDate, streak_val: u32, skipped: Option<&Calendar>) -> AdvancingResult<usize> {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -72,10 +114,7 @@ fn part_date_streak_advance(values: &mut Vec<SimpleRulePartValue<NaiveDate>>,
                 AdvancingResult::completed(Some(0))
             } else {
                 values.remove(1);
-                values.insert(1, SimpleRulePartValue::exact(
-                    data.checked_add_days(Days::new(1))
-                        .expect("real date won't suffer from adding a day")
-                ));
+                values.insert(1, SimpleRulePartValue::exact(next_streak_day(data, skipped)));
                 AdvancingResult::hit(Some(0))
             }
         } else {
@@ -84,40 +123,144 @@ fn part_date_streak_advance(values: &mut Vec<SimpleRulePartValue<NaiveDate>>,
                     AdvancingResult::completed(Some(0))
                 } else {
                     values.remove(1);
-                    values.insert(1, SimpleRulePartValue::exact(
-                        data.checked_add_days(Days::new(1))
-                            .expect("real date won't suffer from adding a day")
-                    ));
+                    values.insert(1, SimpleRulePartValue::exact(next_streak_day(data, skipped)));
                     AdvancingResult::hit(Some(0))
                 }
+            } else if skipped.is_some_and(|c| c.contains(data)) {
+                // activity on a skipped day neither counts nor breaks the streak
+                AdvancingResult::hit(None)
             } else {
                 values.clear();
                 for _ in 0..streak_val {
                     values.push(SimpleRulePartValue::exact(NaiveDate::default()));
                 }
                 values.remove(1);
-                values.insert(1, SimpleRulePartValue::exact(
-                    data.checked_add_days(Days::new(1))
-                        .expect("real date won't suffer from adding a day")
-                ));
+                values.insert(1, SimpleRulePartValue::exact(next_streak_day(data, skipped)));
                 AdvancingResult::hit(Some(0))
             }
         }
     }
 }
 
+pub(super) fn part_list_advance(part: &SimpleRulePart<ListItem>, data: &[ListItem]) -> AdvancingResult<usize> {
+    let matched = match part.seq {
+        SimpleRuleSeq::ContainsAll => part.values
+            .iter()
+            .all(|v| data.iter().any(|d| v.matches(d))),
+        SimpleRuleSeq::ContainsAny => part.values
+            .iter()
+            .any(|v| data.iter().any(|d| v.matches(d))),
+        SimpleRuleSeq::Size => {
+            let size = ListItem::Number(data.len() as u32);
+            part.values.iter().all(|v| v.matches(&size))
+        }
+        _ => panic!("unsupported seq for this SimpleRulePart")
+    };
+    if matched {
+        AdvancingResult::completed(None)
+    } else {
+        AdvancingResult::empty()
+    }
+}
+
+pub(super) fn part_number_advance(part: &mut SimpleRulePart<u32>, data: &u32) -> AdvancingResult<usize> {
+    match part.seq {
+        SimpleRuleSeq::Stat(ref stat, window, ref mut buffer) =>
+            part_number_stat_advance(&part.values, data, stat, window, buffer),
+        SimpleRuleSeq::Best(_) | SimpleRuleSeq::Improved(_) =>
+            unreachable!("history parts are advanced by their rule"),
+        _ => part_universal_advance(part, data)
+    }
+}
+
+pub(super) fn part_number_history_advance(part: &SimpleRulePart<u32>, history: &mut HashMap<String, u32>,
+                                          group: String, data: &u32) -> AdvancingResult<usize> {
+    // the first value of a group only sets the baseline
+    let matched = match part.seq {
+        SimpleRuleSeq::Best(_) => {
+            let previous = history.get(&group).copied();
+            history.insert(group, previous.map_or(*data, |p| p.max(*data)));
+            previous.is_some_and(|p| *data > p)
+        }
+        SimpleRuleSeq::Improved(_) => {
+            let previous = history.insert(group, *data);
+            previous
+                .and_then(|p| data.checked_sub(p))
+                .is_some_and(|diff| part.values.iter().all(|v| v.matches(&diff)))
+        }
+        _ => unreachable!("only history seqs are remembered")
+    };
+    if matched {
+        AdvancingResult::completed(None)
+    } else {
+        AdvancingResult::empty()
+    }
+}
+
+fn part_number_stat_advance(values: &[SimpleRulePartValue<u32>], data: &u32, stat: &SimpleRuleStat,
+                            window: u32, buffer: &mut Vec<u32>) -> AdvancingResult<usize> {
+    buffer.push(*data);
+    if buffer.len() > window as usize {
+        buffer.remove(0);
+    }
+    if buffer.len() < window as usize {
+        AdvancingResult::hit(None)
+    } else {
+        let stat_val = compute_stat(stat, buffer);
+        if values.iter().all(|v| v.matches_stat(stat_val)) {
+            AdvancingResult::completed(None)
+        } else {
+            AdvancingResult::hit(None)
+        }
+    }
+}
+
+fn compute_stat(stat: &SimpleRuleStat, buffer: &[u32]) -> f64 {
+    match stat {
+        SimpleRuleStat::Avg => buffer.iter().map(|v| *v as f64).sum::<f64>() / buffer.len() as f64,
+        SimpleRuleStat::Max => *buffer.iter().max().expect("full window is never empty") as f64,
+        SimpleRuleStat::Min => *buffer.iter().min().expect("full window is never empty") as f64,
+        SimpleRuleStat::Median => {
+            let mut sorted = buffer.to_vec();
+            sorted.sort_unstable();
+            let mid = sorted.len() / 2;
+            if sorted.len() % 2 == 0 {
+                (sorted[mid - 1] as f64 + sorted[mid] as f64) / 2.0
+            } else {
+                sorted[mid] as f64
+            }
+        }
+    }
+}
+
 pub(super) fn part_universal_advance<T>(part: &mut SimpleRulePart<T>, data: &T) -> AdvancingResult<usize>
-where T: PartialEq + Eq + PartialOrd + Ord + Clone {
+where T: PartValue {
     match part.seq {
-        SimpleRuleSeq::Any => part_universal_any_advance(&mut part.values, data),
-        SimpleRuleSeq::All => part_universal_all_advance(&mut part.values, data),
-        SimpleRuleSeq::Order => part_universal_order_advance(&mut part.values, data),
+        SimpleRuleSeq::Any => part_universal_any_advance(&part.values, data),
+        SimpleRuleSeq::All => part_universal_all_advance(&part.values, data),
+        SimpleRuleSeq::Order => part_universal_order_advance(&part.values, data),
+        SimpleRuleSeq::Nth(nth, ref mut seen) => part_universal_nth_advance(&part.values, data, nth, seen),
         _ => panic!("unsupported seq for this SimpleRulePart")
     }
 }
 
-fn part_universal_order_advance<T>(values: &mut Vec<SimpleRulePartValue<T>>, data: &T) -> AdvancingResult<usize>
-where T: PartialEq + Eq + PartialOrd + Ord + Clone {
+fn part_universal_nth_advance<T>(values: &[SimpleRulePartValue<T>], data: &T, nth: u32, seen: &mut u32)
+    -> AdvancingResult<usize> where T: PartValue {
+    // no values means every occurrence counts
+    if !values.is_empty() && !values.iter().any(|v| v.matches(data)) {
+        AdvancingResult::empty()
+    } else {
+        *seen += 1;
+        if *seen % nth == 0 {
+            AdvancingResult::completed(None)
+        } else {
+            AdvancingResult::hit(None)
+        }
+    }
+}
+
+fn part_universal_order_advance<T>(values: &[SimpleRulePartValue<T>], data: &T) -> AdvancingResult<usize>
+where T: PartValue {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -134,8 +277,8 @@ where T: PartialEq + Eq + PartialOrd + Ord + Clone {
     }
 }
 
-fn part_universal_all_advance<T>(values: &mut Vec<SimpleRulePartValue<T>>, data: &T) -> AdvancingResult<usize>
-where T: PartialEq + Eq + PartialOrd + Ord + Clone {
+fn part_universal_all_advance<T>(values: &[SimpleRulePartValue<T>], data: &T) -> AdvancingResult<usize>
+where T: PartValue {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -154,8 +297,8 @@ where T: PartialEq + Eq + PartialOrd + Ord + Clone {
     }
 }
 
-fn part_universal_any_advance<T>(values: &mut Vec<SimpleRulePartValue<T>>, data: &T) -> AdvancingResult<usize>
-where T: PartialEq + Eq + PartialOrd + Ord + Clone {
+fn part_universal_any_advance<T>(values: &[SimpleRulePartValue<T>], data: &T) -> AdvancingResult<usize>
+where T: PartValue {
     if values.is_empty() {
         AdvancingResult::completed(None)
     } else {
@@ -172,6 +315,11 @@ where T: PartialEq + Eq + PartialOrd + Ord + Clone {
 
 pub(super) fn part_reset<T>(part: &mut SimpleRulePart<T>) where T: Clone {
     part.values = part.og_values.clone();
+    match part.seq {
+        SimpleRuleSeq::Stat(_, _, ref mut buffer) => buffer.clear(),
+        SimpleRuleSeq::Nth(_, ref mut seen) => *seen = 0,
+        _ => {}
+    }
 }
 
 pub(super) fn part_needs_reset() -> bool {
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 use chrono::{NaiveDate, NaiveTime};
 use crate::{Advancing, AdvancingResult, AdvancingResultType};
-use crate::event::EventPartValue;
-use crate::simple_rule::{SimpleRule, SimpleRulePart, WrappedSimpleRulePart};
+use crate::event::{Duration, Event, EventPartValue, ListItem};
+use crate::simple_rule::{SimpleRule, SimpleRulePart, SimpleRuleSeq, WrappedSimpleRulePart};
 
-pub(super) fn clean_parts(rule: &mut SimpleRule, parts_res: &Vec<(String, AdvancingResult<usize>)>) {
+pub(super) fn clean_parts(rule: &mut SimpleRule, parts_res: &[(String, AdvancingResult<usize>)]) {
     parts_res
         .iter()
         .for_each(|(k, v)| {
@@ -20,29 +20,63 @@ pub(super) fn clean_parts(rule: &mut SimpleRule, parts_res: &Vec<(String, Advanc
                         WrappedSimpleRulePart::Date(ref mut p) => {
                             p.values.remove(index);
                         }
+                        WrappedSimpleRulePart::Duration(ref mut p) => {
+                            p.values.remove(index);
+                        }
+                        WrappedSimpleRulePart::List(ref mut p) => {
+                            p.values.remove(index);
+                        }
                     };
                 }
             }
         });
 }
 
-pub(super) fn all_parts_completed(parts_res: &Vec<(String, AdvancingResult<usize>)>) -> bool {
+// undated hits can't expire
+pub(super) fn record_hit(rule: &mut SimpleRule, event: &Event) {
+    if let Some(EventPartValue::DateBased(date)) = event.parts.get("on") {
+        rule.periods.hit(date);
+    }
+}
+
+pub(super) fn all_parts_completed(parts_res: &[(String, AdvancingResult<usize>)]) -> bool {
     parts_res
         .iter()
         .all(|(_, val)| val.res_type == AdvancingResultType::Completed)
 }
 
-pub(super) fn advance_part(rule_parts: &mut HashMap<String, WrappedSimpleRulePart>,
+pub(super) fn required_parts_present(rule: &SimpleRule, event: &Event) -> bool {
+    rule.parts
+        .keys()
+        .filter(|k| !rule.optional.contains(*k))
+        .all(|k| event.parts.contains_key(k))
+}
+
+pub(super) fn is_stateful(rule_parts: &HashMap<String, WrappedSimpleRulePart>, part_name: &String) -> bool {
+    matches!(
+        rule_parts.get(part_name).map(|p| p.seq()),
+        Some(SimpleRuleSeq::Stat(..) | SimpleRuleSeq::Best(_) | SimpleRuleSeq::Improved(_) | SimpleRuleSeq::Nth(..))
+    )
+}
+
+pub(super) fn advance_part(rule: &mut SimpleRule, event: &Event,
                            part_name: &String, event_pv: &EventPartValue)
                            -> Option<(String, AdvancingResult<usize>)> {
-    if let Some(wrap_p) = rule_parts.get_mut(part_name) {
+    if let Some(wrap_p) = rule.parts.get_mut(part_name) {
         let adv_res = match wrap_p {
-            WrappedSimpleRulePart::Number(ref mut p) =>
-                advance_num_part(p, event_pv),
+            WrappedSimpleRulePart::Number(ref mut p) => match p.seq {
+                SimpleRuleSeq::Best(ref group) | SimpleRuleSeq::Improved(ref group) =>
+                    advance_history_part(p, &mut rule.memory, part_name, group, event, event_pv),
+                _ => advance_num_part(p, event_pv)
+            },
             WrappedSimpleRulePart::Time(ref mut p) =>
                 advance_time_part(p, event_pv),
             WrappedSimpleRulePart::Date(ref mut p) =>
-                advance_date_part(p, event_pv)
+                advance_date_part(p, event_pv),
+            WrappedSimpleRulePart::Duration(ref mut p) =>
+                advance_duration_part(p, event_pv),
+            WrappedSimpleRulePart::List(ref mut p) =>
+                advance_list_part(p, event_pv)
         };
         if let AdvancingResultType::None = adv_res.res_type {
             None
@@ -54,11 +88,49 @@ pub(super) fn advance_part(rule_parts: &mut HashMap<String, WrappedSimpleRulePar
     }
 }
 
+fn advance_history_part(part: &SimpleRulePart<u32>, memory: &mut HashMap<String, HashMap<String, u32>>,
+                        part_name: &str, group: &Option<String>, event: &Event,
+                        event_pv: &EventPartValue) -> AdvancingResult<usize> {
+    let group_val = match group {
+        Some(group) => match event.parts.get(group) {
+            Some(val) => val.to_string(),
+            None => return AdvancingResult::empty()
+        },
+        None => String::new()
+    };
+    if let EventPartValue::NumberBased(e_val) = event_pv {
+        let history = memory
+            .entry(part_name.to_owned())
+            .or_default();
+        super::part_number_history_advance(part, history, group_val, e_val)
+    } else {
+        // a value of another type can't match the part
+        AdvancingResult::empty()
+    }
+}
+
+fn advance_duration_part(part: &mut SimpleRulePart<Duration>, event_pv: &EventPartValue) -> AdvancingResult<usize> {
+    // older frontends send durations as plain numbers of seconds
+    match event_pv {
+        EventPartValue::DurationBased(e_val) => part.advance(e_val),
+        EventPartValue::NumberBased(e_val) => part.advance(&Duration(*e_val)),
+        _ => AdvancingResult::empty()
+    }
+}
+
+fn advance_list_part(part: &mut SimpleRulePart<ListItem>, event_pv: &EventPartValue) -> AdvancingResult<usize> {
+    if let EventPartValue::List(e_val) = event_pv {
+        part.advance(e_val)
+    } else {
+        AdvancingResult::empty()
+    }
+}
+
 fn advance_date_part(part: &mut SimpleRulePart<NaiveDate>, event_pv: &EventPartValue) -> AdvancingResult<usize> {
     if let EventPartValue::DateBased(e_val) = event_pv {
         part.advance(e_val)
     } else {
-        unreachable!("logical contract guarantees proper flow")
+        AdvancingResult::empty()
     }
 }
 
@@ -66,7 +138,7 @@ fn advance_time_part(part: &mut SimpleRulePart<NaiveTime>, event_pv: &EventPartV
     if let EventPartValue::TimeBased(e_val) = event_pv {
         part.advance(e_val)
     } else {
-        unreachable!("logical contract guarantees proper flow")
+        AdvancingResult::empty()
     }
 }
 
@@ -74,6 +146,6 @@ fn advance_num_part(part: &mut SimpleRulePart<u32>, event_pv: &EventPartValue) -
     if let EventPartValue::NumberBased(e_val) = event_pv {
         part.advance(e_val)
     } else {
-        unreachable!("logical contract guarantees proper flow")
+        AdvancingResult::empty()
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+use common::calendar::NamedCalendar;
 use common::compound_rule::NamedCompoundRule;
 use common::rule_result::NamedRuleResult;
 use common::simple_rule::NamedSimpleRule;
+use crate::parsing::calendar::error::CalendarParseError;
 use crate::parsing::compound_rule::error::CompoundRuleParseError;
 use crate::parsing::rule_result::error::RuleResultParseError;
 use crate::parsing::simple_rule::error::SimpleRuleParseError;
@@ -17,4 +19,8 @@ pub fn parse_compound_rule(input: &str) -> Result<NamedCompoundRule, CompoundRul
 
 pub fn parse_rule_result(input: &str) -> Result<NamedRuleResult, RuleResultParseError> {
     parsing::Parser::parse_rule_result(input)
+}
+
+pub fn parse_calendar(input: &str) -> Result<NamedCalendar, CalendarParseError> {
+    parsing::Parser::parse_calendar(input)
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+use common::calendar::NamedCalendar;
 use common::compound_rule::NamedCompoundRule;
 use common::rule_result::NamedRuleResult;
 use common::simple_rule::NamedSimpleRule;
+use crate::parsing::calendar::error::CalendarParseError;
 use crate::parsing::compound_rule::error::CompoundRuleParseError;
 use crate::parsing::rule_result::error::RuleResultParseError;
 use crate::parsing::simple_rule::error::SimpleRuleParseError;
@@ -8,6 +10,7 @@ use crate::parsing::simple_rule::error::SimpleRuleParseError;
 pub mod simple_rule;
 pub mod compound_rule;
 pub mod rule_result;
+pub mod calendar;
 
 #[derive(pest_derive::Parser)]
 #[grammar = "grammar.pest"]
@@ -35,4 +38,11 @@ impl Parser {
             .expect("match guarantees non-empty iterator");
         rule_result::parse(data)
     }
+    pub(super) fn parse_calendar(input: &str) -> Result<NamedCalendar, CalendarParseError> {
+        let data = <Self as pest::Parser<_>>::parse(Rule::calendar, input)
+            .map_err(|_| CalendarParseError::NoMatch)?
+            .next()
+            .expect("match guarantees non-empty iterator");
+        calendar::parse(data)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use common::calendar::{Calendar, NamedCalendar};
+use pest::iterators::Pair;
+
+use crate::parsing::calendar::error::CalendarParseError;
+use crate::parsing::Rule;
+use crate::parsing::simple_rule;
+
+pub mod error;
+
+pub(super) fn parse(data: Pair<Rule>) -> Result<NamedCalendar, CalendarParseError> {
+    let mut name = String::new();
+    let mut dates = Vec::new();
+    for data_chunk in data.into_inner() {
+        match data_chunk.as_rule() {
+            Rule::name => name.push_str(data_chunk.as_str()),
+            Rule::calendar_content => dates.push(data_chunk.as_str()),
+            _ => unreachable!()
+        }
+    }
+    // dates are written the same way as in the `on` part of simple rules
+    let dates = simple_rule::parse_values(dates.into_iter(), simple_rule::parse_date_value)
+        .map_err(|_| CalendarParseError::IncorrectContent)?;
+    Ok(NamedCalendar::new(name, Calendar::new(dates)))
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+pub enum CalendarParseError {
+    NoMatch, IncorrectContent
+}
+
+impl Debug for CalendarParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Display for CalendarParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            CalendarParseError::NoMatch => "couldn't match input with the calendar",
+            CalendarParseError::IncorrectContent => "one of the dates is incorrect"
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for CalendarParseError {}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use chrono::NaiveDate;
-use common::compound_rule::{CompoundRule, CompoundRuleSeq, NamedCompoundRule};
+use common::compound_rule::{CompoundRule, CompoundRuleGap, CompoundRuleSeq, NamedCompoundRule};
 use pest::iterators::Pair;
 
 use crate::parsing::compound_rule::error::CompoundRuleParseError;
@@ -14,10 +14,20 @@ pub(super) fn parse(data: Pair<Rule>) -> Result<NamedCompoundRule, CompoundRuleP
     let mut every = 1;
     let mut seq = CompoundRuleSeq::Any;
     let mut content = Vec::new();
+    let mut within = None;
+    let mut without = Vec::new();
+    let mut without_window = None;
+    let mut gap = None;
+    let mut resets = None;
+    let mut expires = None;
+    let mut active = None;
+    let mut locked = false;
     for data_chunk in data.into_inner() {
         match data_chunk.as_rule() {
             Rule::name => name.push_str(data_chunk.as_str()),
+            Rule::locked => locked = true,
             Rule::repeat => iterations = -1,
+            Rule::repeat_count => iterations = parse_repeat_count(data_chunk.as_str())?,
             Rule::compound_rule_every_content => every = {
                 let parsed = parse_numeric_val(data_chunk.as_str())?;
                 if parsed > 0 {
@@ -28,10 +38,50 @@ pub(super) fn parse(data: Pair<Rule>) -> Result<NamedCompoundRule, CompoundRuleP
             },
             Rule::compound_rule_seq => seq = parse_seq(data_chunk.as_str())?,
             Rule::compound_rule_content => content.push(data_chunk.as_str().to_owned()),
+            Rule::compound_rule_within => within = Some(parse_seq_num(data_chunk.as_str().strip_suffix('d'))?),
+            Rule::compound_rule_without => without.push(data_chunk.as_str().to_owned()),
+            Rule::compound_rule_without_window =>
+                without_window = Some(parse_seq_num(data_chunk.as_str().strip_suffix('d'))?),
+            Rule::compound_rule_gap => gap = Some(parse_gap(data_chunk.as_str())?),
+            Rule::compound_rule_expires => expires = Some(parse_seq_num(data_chunk.as_str().strip_suffix('d'))?),
+            Rule::compound_rule_active => active = Some(data_chunk.as_str().parse()
+                .map_err(|_| IncorrectContent)?),
+            Rule::compound_rule_resets => resets = Some(data_chunk.as_str().parse()
+                .map_err(|_| IncorrectContent)?),
+            Rule::EOI => (),
             _ => unreachable!()
         }
     }
-    Ok(NamedCompoundRule::new(name, CompoundRule::new(iterations, every, seq, content)))
+    if let CompoundRuleSeq::AtLeast(needed) | CompoundRuleSeq::DailyStreak(_, needed, ..) = seq {
+        if needed as usize > content.len() {
+            return Err(IncorrectContent);
+        }
+    }
+    // `all` as a daily requirement means every referenced rule
+    if let CompoundRuleSeq::DailyStreak(_, ref mut needed @ 0, ..) = seq {
+        *needed = content.len() as u32;
+    }
+    // streaks already measure time on their own
+    if within.is_some() && matches!(seq, CompoundRuleSeq::Streak(..) | CompoundRuleSeq::DailyStreak(..)) {
+        return Err(IncorrectContent);
+    }
+    // gaps only make sense between the steps of a sequence
+    if gap.is_some() && !matches!(seq, CompoundRuleSeq::Order | CompoundRuleSeq::StrictOrder) {
+        return Err(IncorrectContent);
+    }
+    if without.iter().any(|w| content.contains(w)) {
+        return Err(IncorrectContent);
+    }
+    let mut rule = CompoundRule::new(iterations, every, seq, content);
+    rule.within = within;
+    rule.without = without;
+    rule.without_window = without_window;
+    rule.gap = gap;
+    rule.periods.resets = resets;
+    rule.periods.expires = expires;
+    rule.active = active;
+    rule.locked = locked;
+    Ok(NamedCompoundRule::new(name, rule))
 }
 
 fn parse_seq(input: &str) -> Result<CompoundRuleSeq, CompoundRuleParseError> {
@@ -41,27 +91,49 @@ fn parse_seq(input: &str) -> Result<CompoundRuleSeq, CompoundRuleParseError> {
         "all" => CompoundRuleSeq::All,
         "seq" => CompoundRuleSeq::Order,
         "streak" => {
-            let streak_num = if let Some(item) = chunks.next() {
-                let parsed = parse_numeric_val(item)?;
-                if parsed > 0 {
-                    parsed
-                } else {
-                    return Err(IncorrectContent);
-                }
-            } else {
-                return Err(IncorrectContent);
-            };
+            let streak_num = parse_seq_num(chunks.next())?;
             let mut streak_vals = Vec::new();
             for _ in 0..streak_num {
                 streak_vals.push(NaiveDate::default());
             }
-            CompoundRuleSeq::Streak(streak_num, streak_vals)
+            // daily requirements, with 0 standing for all referenced rules until they're known
+            match chunks.next() {
+                Some("all") => CompoundRuleSeq::DailyStreak(streak_num, 0, streak_vals, Vec::new()),
+                Some("atleast") =>
+                    CompoundRuleSeq::DailyStreak(streak_num, parse_seq_num(chunks.next())?, streak_vals, Vec::new()),
+                _ => CompoundRuleSeq::Streak(streak_num, streak_vals)
+            }
         },
+        "atleast" => CompoundRuleSeq::AtLeast(parse_seq_num(chunks.next())?),
+        "strictseq" => CompoundRuleSeq::StrictOrder,
         _ => return Err(CompoundRuleParseError::UnsupportedSeq)
     };
     Ok(res)
 }
 
+fn parse_gap(input: &str) -> Result<CompoundRuleGap, CompoundRuleParseError> {
+    match input.strip_suffix('d') {
+        Some(days) => Ok(CompoundRuleGap::Days(parse_numeric_val(days)?)),
+        None => Ok(CompoundRuleGap::Events(parse_seq_num(Some(input))?))
+    }
+}
+
+fn parse_repeat_count(input: &str) -> Result<i32, CompoundRuleParseError> {
+    match input.parse() {
+        Ok(val) if val > 0 => Ok(val),
+        _ => Err(IncorrectContent)
+    }
+}
+
+fn parse_seq_num(input: Option<&str>) -> Result<u32, CompoundRuleParseError> {
+    let parsed = parse_numeric_val(input.ok_or(IncorrectContent)?)?;
+    if parsed > 0 {
+        Ok(parsed)
+    } else {
+        Err(IncorrectContent)
+    }
+}
+
 fn parse_numeric_val(input: &str) -> Result<u32, CompoundRuleParseError> {
-    Ok(input.parse().map_err(|_| IncorrectContent)?)
+    input.parse().map_err(|_| IncorrectContent)
 }
\ No newline at end of file
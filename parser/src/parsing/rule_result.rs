@@ -19,9 +19,10 @@ pub(super) fn parse(data: Pair<Rule>) -> Result<NamedRuleResult, RuleResultParse
                 for data_chunk in data_chunk.into_inner() {
                     match data_chunk.as_rule() {
                         Rule::repeat => iterations = -1,
+                        Rule::repeat_count => iterations = parse_repeat_count(data_chunk.as_str())?,
                         Rule::result_kind => kind = parse_kind(data_chunk.as_str())?,
                         Rule::result_seq => seq = parse_seq(data_chunk.as_str())?,
-                        Rule::result_arg => args.push(data_chunk.as_str().to_owned()),
+                        Rule::result_arg => args.push(parse_arg(data_chunk)),
                         _ => unreachable!()
                     }
                 }
@@ -33,6 +34,22 @@ pub(super) fn parse(data: Pair<Rule>) -> Result<NamedRuleResult, RuleResultParse
     Ok(NamedRuleResult::new(name, RuleResult::new(values)))
 }
 
+// quoted templates are stored without their quotes
+fn parse_arg(data: Pair<Rule>) -> String {
+    let raw = data.as_str();
+    match data.into_inner().next() {
+        Some(template) => template.as_str().to_owned(),
+        None => raw.to_owned()
+    }
+}
+
+fn parse_repeat_count(input: &str) -> Result<i32, RuleResultParseError> {
+    match input.parse() {
+        Ok(val) if val > 0 => Ok(val),
+        _ => Err(RuleResultParseError::IncorrectRepeat)
+    }
+}
+
 fn parse_seq(input: &str) -> Result<RuleResultSeq, RuleResultParseError> {
     let res = match input {
         "all" => RuleResultSeq::All,
@@ -52,6 +69,10 @@ fn parse_kind(input: &str) -> Result<RuleResultKind, RuleResultParseError> {
         "offer" => RuleResultKind::Offer,
         "open" => RuleResultKind::Open,
         "restart" => RuleResultKind::Restart,
+        "unlock" => RuleResultKind::Unlock,
+        "lock" => RuleResultKind::Lock,
+        "disable" => RuleResultKind::Disable,
+        "reset" => RuleResultKind::Reset,
         _ => return Err(RuleResultParseError::UnsupportedKind)
     };
     Ok(res)
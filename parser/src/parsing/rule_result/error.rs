@@ -3,7 +3,7 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 pub enum RuleResultParseError {
-    NoMatch, UnsupportedSeq, UnsupportedKind
+    NoMatch, UnsupportedSeq, UnsupportedKind, IncorrectRepeat
 }
 
 impl Debug for RuleResultParseError {
@@ -18,6 +18,7 @@ impl Display for RuleResultParseError {
             RuleResultParseError::NoMatch => "couldn't match input with the result",
             RuleResultParseError::UnsupportedSeq => "provided unsupported seq qualifier",
             RuleResultParseError::UnsupportedKind => "provided unsupported kind qualifier",
+            RuleResultParseError::IncorrectRepeat => "repeat count must be a positive number",
         };
         f.write_str(msg)
     }
@@ -1,7 +1,9 @@
-use std::collections::HashMap;
-use std::str::{FromStr, Split};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 
-use common::simple_rule::{NamedSimpleRule, SimpleRule, SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, WrappedSimpleRulePart};
+use chrono::NaiveDate;
+use common::event::ListItem;
+use common::simple_rule::{DatePattern, NamedSimpleRule, SimpleRule, SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, SimpleRuleStat, WrappedSimpleRulePart};
 use pest::iterators::Pair;
 
 use crate::parsing::Rule;
@@ -9,27 +11,53 @@ use crate::parsing::simple_rule::error::SimpleRuleParseError;
 
 pub mod error;
 
+// raw chunks of a single rule part, as matched by the grammar
+#[derive(Default)]
+struct RawPart<'a> {
+    kw: Option<&'a str>,
+    seq: Option<&'a str>,
+    cont: Option<&'a str>,
+    window: Option<&'a str>,
+    group: Option<&'a str>,
+    skipped: Option<&'a str>,
+    optional: bool
+}
+
 pub(super) fn parse(data: Pair<Rule>) -> Result<NamedSimpleRule, SimpleRuleParseError> {
     let mut name = String::new();
     let mut iterations = 1;
+    let mut category = None;
+    let mut strict = false;
+    let mut locked = false;
+    let mut resets = None;
+    let mut expires = None;
+    let mut active = None;
     let mut parts = Vec::new();
     for data_chunk in data.into_inner() {
         match data_chunk.as_rule() {
             Rule::name => name.push_str(data_chunk.as_str()),
+            Rule::simple_rule_category => category = Some(data_chunk.as_str().to_owned()),
+            Rule::strict => strict = true,
+            Rule::locked => locked = true,
             Rule::simple_rule_part => {
-                let mut kw = None;
-                let mut seq = None;
-                let mut cont = None;
+                let mut raw = RawPart::default();
                 for data_chunk in data_chunk.into_inner() {
                     match data_chunk.as_rule() {
-                        Rule::simple_rule_keyword => kw = Some(data_chunk.as_str()),
-                        Rule::simple_rule_seq => seq = Some(data_chunk.as_str()),
-                        Rule::simple_rule_content => cont = Some(data_chunk.as_str()),
+                        Rule::optional => raw.optional = true,
+                        Rule::simple_rule_keyword => raw.kw = Some(data_chunk.as_str()),
+                        Rule::simple_rule_seq => raw.seq = Some(data_chunk.as_str()),
+                        Rule::simple_rule_content => raw.cont = Some(data_chunk.as_str()),
+                        Rule::simple_rule_window => raw.window = Some(data_chunk.as_str()),
+                        Rule::simple_rule_group => raw.group = Some(data_chunk.as_str()),
+                        Rule::simple_rule_skipped => raw.skipped = Some(data_chunk.as_str()),
                         _ => unreachable!()
                     }
                 }
-                if let Some("repeat") = kw {
-                    let cont = cont.expect("match guarantees non-empty repeat");
+                if let Some("repeat") = raw.kw {
+                    if raw.optional {
+                        return Err(SimpleRuleParseError::IncorrectContent);
+                    }
+                    let cont = raw.cont.expect("match guarantees non-empty repeat");
                     iterations = if cont.eq("+") {
                         -1
                     } else {
@@ -37,49 +65,107 @@ pub(super) fn parse(data: Pair<Rule>) -> Result<NamedSimpleRule, SimpleRuleParse
                             .map_err(|_| SimpleRuleParseError::IncorrectContent)?
                     }
                 } else {
-                    parts.push((kw, seq, cont));
+                    parts.push(raw);
                 }
             }
+            Rule::simple_rule_expires => expires = Some(parse_expiry(data_chunk.as_str())?),
+            Rule::simple_rule_resets => resets = Some(data_chunk.as_str().parse()
+                .map_err(|_| SimpleRuleParseError::IncorrectContent)?),
+            Rule::simple_rule_active => active = Some(data_chunk.as_str().parse()
+                .map_err(|_| SimpleRuleParseError::IncorrectContent)?),
+            Rule::EOI => (),
             _ => unreachable!()
         }
     }
     if iterations == 0 {
         return Err(SimpleRuleParseError::IncorrectContent);
     }
-    Ok(NamedSimpleRule::new(name, parse_rule(iterations, parts)?))
+    // parts of other rules may be missing anyway
+    if !strict && parts.iter().any(|p| p.optional) {
+        return Err(SimpleRuleParseError::OptionalWithoutStrict);
+    }
+    let mut rule = parse_rule(iterations, strict, parts)?;
+    rule.category = category;
+    rule.periods.resets = resets;
+    rule.periods.expires = expires;
+    rule.active = active;
+    rule.locked = locked;
+    Ok(NamedSimpleRule::new(name, rule))
 }
 
-fn parse_rule(iterations: i32, kw_seq_cont: Vec<(Option<&str>, Option<&str>, Option<&str>)>)
-    -> Result<SimpleRule, SimpleRuleParseError> {
+fn parse_expiry(input: &str) -> Result<u32, SimpleRuleParseError> {
+    match input.strip_suffix('d').map(str::parse) {
+        Some(Ok(days)) if days > 0 => Ok(days),
+        _ => Err(SimpleRuleParseError::IncorrectContent)
+    }
+}
+
+fn parse_rule(iterations: i32, strict: bool, raw_parts: Vec<RawPart>) -> Result<SimpleRule, SimpleRuleParseError> {
     let mut parts: HashMap<String, WrappedSimpleRulePart> = HashMap::new();
-    for data in kw_seq_cont {
-        parts.insert(
-            data.0.expect("hit guarantees presence of a value").to_owned(),
-            parse_wrapped_part(data)?
-        );
+    let mut optional = HashSet::new();
+    for raw in raw_parts {
+        let kw = raw.kw.expect("hit guarantees presence of a value").to_owned();
+        if raw.optional {
+            optional.insert(kw.clone());
+        }
+        parts.insert(kw, parse_wrapped_part(raw)?);
     }
-    Ok(SimpleRule::new(iterations, parts))
+    let mut rule = SimpleRule::new(iterations, parts);
+    rule.strict = strict;
+    rule.optional = optional;
+    Ok(rule)
 }
 
-fn parse_wrapped_part(kw_seq_cont: (Option<&str>, Option<&str>, Option<&str>))
-    -> Result<WrappedSimpleRulePart, SimpleRuleParseError> {
-    let res = match kw_seq_cont.0.expect("hit guarantees presence of a value") {
-        "on" => WrappedSimpleRulePart::Date(parse_part(kw_seq_cont.1, kw_seq_cont.2)?),
-        "at" => WrappedSimpleRulePart::Time(parse_part(kw_seq_cont.1, kw_seq_cont.2)?),
-        _ => WrappedSimpleRulePart::Number(parse_part(kw_seq_cont.1, kw_seq_cont.2)?)
+fn parse_wrapped_part(raw: RawPart) -> Result<WrappedSimpleRulePart, SimpleRuleParseError> {
+    let res = match raw.kw.expect("hit guarantees presence of a value") {
+        "on" => WrappedSimpleRulePart::Date(reject_number_only(parse_part(&raw, parse_date_value)?)?),
+        "at" => WrappedSimpleRulePart::Time(reject_date_only(reject_number_only(parse_part(&raw, parse_value)?)?)?),
+        "took" => WrappedSimpleRulePart::Duration(reject_date_only(reject_number_only(parse_part(&raw, parse_value)?)?)?),
+        _ if matches!(raw.seq, Some("contains" | "contains_all" | "contains_any" | "size")) =>
+            WrappedSimpleRulePart::List(parse_list_part(&raw)?),
+        _ => WrappedSimpleRulePart::Number(reject_date_only(parse_part(&raw, parse_number_value)?)?)
     };
     Ok(res)
 }
 
-fn parse_part<T>(seq: Option<&str>, cont: Option<&str>)
-    -> Result<SimpleRulePart<T>, SimpleRuleParseError> where T: Clone + Default + FromStr {
-    let cont = cont.expect("hit guarantees presence of a value");
-    let seq = parse_seq(seq, &mut cont.split(','))?;
+// calendars, day streaks and selected days only work with date parts
+fn reject_date_only<T>(part: SimpleRulePart<T>) -> Result<SimpleRulePart<T>, SimpleRuleParseError> where T: Clone {
+    match part.seq {
+        SimpleRuleSeq::In(..) | SimpleRuleSeq::NotIn(..) | SimpleRuleSeq::SkippingStreak(..)
+        | SimpleRuleSeq::Streak(_) | SimpleRuleSeq::Selected(_) =>
+            Err(SimpleRuleParseError::UnsupportedSeq),
+        _ => Ok(part)
+    }
+}
+
+// statistics and history comparisons only work with number parts
+fn reject_number_only<T>(part: SimpleRulePart<T>) -> Result<SimpleRulePart<T>, SimpleRuleParseError> where T: Clone {
+    match part.seq {
+        SimpleRuleSeq::Stat(..) | SimpleRuleSeq::Best(_) | SimpleRuleSeq::Improved(_) =>
+            Err(SimpleRuleParseError::UnsupportedSeq),
+        _ => Ok(part)
+    }
+}
+
+// parses a single value of a part, e.g. `>=3` or `2000.01.01..2000.01.31`
+pub(super) type ValueParser<T> = fn(&str) -> Result<SimpleRulePartValue<T>, SimpleRuleParseError>;
+
+fn parse_part<T>(raw: &RawPart, parse: ValueParser<T>) -> Result<SimpleRulePart<T>, SimpleRuleParseError> where T: Clone + Default {
+    let cont = raw.cont.expect("hit guarantees presence of a value");
+    let seq = parse_seq(raw, &mut cont.split(','))?;
     let values = match seq {
         SimpleRuleSeq::Any | SimpleRuleSeq::All | SimpleRuleSeq::Order
-            => parse_values(cont.split(','))?,
-        SimpleRuleSeq::Streak(streak_val) => {
-            if streak_val <= 0 {
+            => parse_values(cont.split(','), parse)?,
+        SimpleRuleSeq::Stat(..) => parse_conditions(cont, parse)?,
+        SimpleRuleSeq::Best(_) => Vec::new(),
+        SimpleRuleSeq::Improved(_) => if raw.seq.is_some() {
+            parse_conditions(cont, parse)?
+        } else {
+            // bare `improved` accepts any improvement
+            vec!(SimpleRulePartValue::new(SimpleRuleBorder::Greater, T::default(), None))
+        }
+        SimpleRuleSeq::Streak(streak_val) | SimpleRuleSeq::SkippingStreak(streak_val, ..) => {
+            if streak_val == 0 {
                 return Err(SimpleRuleParseError::IncorrectContent)
             }
             let mut values = Vec::new();
@@ -88,22 +174,95 @@ fn parse_part<T>(seq: Option<&str>, cont: Option<&str>)
             }
             values
         }
-        SimpleRuleSeq::Selected(_) => Vec::new()
+        SimpleRuleSeq::Selected(_) | SimpleRuleSeq::In(..) | SimpleRuleSeq::NotIn(..) => Vec::new(),
+        SimpleRuleSeq::Nth(..) => parse_values(cont.split(',').skip(1), parse)?,
+        SimpleRuleSeq::ContainsAll | SimpleRuleSeq::ContainsAny | SimpleRuleSeq::Size =>
+            return Err(SimpleRuleParseError::UnsupportedSeq)
     };
     Ok(SimpleRulePart::new(seq, values))
 }
 
-fn parse_values<T>(cont: Split<char>) -> Result<Vec<SimpleRulePartValue<T>>, SimpleRuleParseError>
-where T: Clone + FromStr {
-    let mut vals = Vec::new();
-    for c in cont {
-        if !c.contains('*') {
-            vals.push(parse_value(c)?)
+fn parse_list_part(raw: &RawPart) -> Result<SimpleRulePart<ListItem>, SimpleRuleParseError> {
+    let cont = raw.cont.expect("hit guarantees presence of a value");
+    let seq = parse_seq(raw, &mut cont.split(','))?;
+    let values = if let SimpleRuleSeq::Size = seq {
+        // sizes are always numbers
+        parse_conditions::<u32>(cont, parse_value)?
+            .into_iter()
+            .map(SimpleRulePartValue::from)
+            .collect()
+    } else {
+        let mut values = Vec::new();
+        for item in cont.split(',') {
+            if item.is_empty() {
+                return Err(SimpleRuleParseError::IncorrectContent);
+            }
+            values.push(SimpleRulePartValue::exact(item.parse::<ListItem>()
+                .map_err(|_| SimpleRuleParseError::IncorrectContent)?));
         }
+        values
+    };
+    Ok(SimpleRulePart::new(seq, values))
+}
+
+fn parse_conditions<T>(cont: &str, parse: ValueParser<T>) -> Result<Vec<SimpleRulePartValue<T>>, SimpleRuleParseError>
+where T: Clone {
+    let values = parse_values(cont.split(','), parse)?;
+    if values.is_empty() {
+        Err(SimpleRuleParseError::IncorrectContent)
+    } else {
+        Ok(values)
+    }
+}
+
+pub(super) fn parse_values<'a, T>(cont: impl Iterator<Item = &'a str>, parse: ValueParser<T>)
+    -> Result<Vec<SimpleRulePartValue<T>>, SimpleRuleParseError> where T: Clone {
+    let mut vals = Vec::new();
+    // a lone asterisk matches everything and is skipped
+    for c in cont.filter(|c| *c != "*") {
+        vals.push(parse(c)?)
     }
     Ok(vals)
 }
 
+// numbers can also be checked against an arithmetic progression, e.g. `%5==2`
+fn parse_number_value(input: &str) -> Result<SimpleRulePartValue<u32>, SimpleRuleParseError> {
+    match input.strip_prefix('%') {
+        Some(stepped) => {
+            let (step, offset) = stepped.split_once("==").unwrap_or((stepped, "0"));
+            let parse = |raw: &str| raw.parse::<u32>()
+                .map_err(|_| SimpleRuleParseError::IncorrectContent);
+            let (step, offset) = (parse(step)?, parse(offset)?);
+            // offsets are remainders, so they have to stay below a non-zero step
+            if offset >= step {
+                return Err(SimpleRuleParseError::IncorrectContent);
+            }
+            Ok(SimpleRulePartValue::new(SimpleRuleBorder::Modulo, step, Some(offset)))
+        }
+        None => parse_value(input)
+    }
+}
+
+// dates can also be written as patterns with wildcards, e.g. `*.12.24` or `*.12.20..*.01.06`
+pub(super) fn parse_date_value(input: &str) -> Result<SimpleRulePartValue<NaiveDate>, SimpleRuleParseError> {
+    if !input.contains('*') {
+        return parse_value(input);
+    }
+    let parse = |raw: &str| raw.parse::<DatePattern>()
+        .map_err(|_| SimpleRuleParseError::IncorrectContent);
+    match input.split_once("..") {
+        Some((from, to)) => {
+            let (from, to) = (parse(from)?, parse(to)?);
+            if from.ranges_with(&to) {
+                Ok(SimpleRulePartValue::recurring(from, Some(to)))
+            } else {
+                Err(SimpleRuleParseError::IncorrectContent)
+            }
+        }
+        None => parse(input).map(|from| SimpleRulePartValue::recurring(from, None))
+    }
+}
+
 fn parse_value<T>(input: &str) -> Result<SimpleRulePartValue<T>, SimpleRuleParseError>
 where T: Clone + FromStr {
     let border;
@@ -114,37 +273,33 @@ where T: Clone + FromStr {
         border = SimpleRuleBorder::Between;
         left_val = content.next();
         right_val = Some(content.next()
-            .ok_or_else(|| SimpleRuleParseError::IncorrectContent)?
+            .ok_or(SimpleRuleParseError::IncorrectContent)?
             .replace(".", "-")
             .parse::<T>()
             .map_err(|_| SimpleRuleParseError::IncorrectContent)?);
     } else if input.contains(">=") {
         border = SimpleRuleBorder::GreaterEq;
         left_val = input.split(">=")
-            .skip(1)
-            .next();
+            .nth(1);
     } else if input.contains("<=") {
         border = SimpleRuleBorder::LessEq;
         left_val = input.split("<=")
-            .skip(1)
-            .next();
+            .nth(1);
     } else if input.contains(">") {
         border = SimpleRuleBorder::Greater;
         left_val = input.split(">")
-            .skip(1)
-            .next();
+            .nth(1);
     } else if input.contains("<") {
         border = SimpleRuleBorder::Less;
         left_val = input.split("<")
-            .skip(1)
-            .next();
+            .nth(1);
     } else {
         border = SimpleRuleBorder::Exact;
         left_val = Some(input);
     }
     Ok(SimpleRulePartValue::new(
         border,
-        left_val.ok_or_else(|| SimpleRuleParseError::IncorrectContent)?
+        left_val.ok_or(SimpleRuleParseError::IncorrectContent)?
             .replace(".", "-")
             .parse::<T>()
             .map_err(|_| SimpleRuleParseError::IncorrectContent)?,
@@ -152,35 +307,120 @@ where T: Clone + FromStr {
     ))
 }
 
-fn parse_seq(seq: Option<&str>, cont: &mut Split<char>) -> Result<SimpleRuleSeq, SimpleRuleParseError> {
-    let res = match seq.unwrap_or("any") {
+fn parse_seq<'a>(raw: &RawPart, cont: &mut impl Iterator<Item = &'a str>) -> Result<SimpleRuleSeq, SimpleRuleParseError> {
+    let seq = match (raw.seq, raw.cont) {
+        (Some(seq), _) => seq,
+        (None, Some(kw @ ("best" | "improved"))) => kw,
+        _ => "any"
+    };
+    let res = match seq {
         "any" => SimpleRuleSeq::Any,
         "all" => SimpleRuleSeq::All,
         "seq" => SimpleRuleSeq::Order,
-        "streak" => SimpleRuleSeq::Streak(cont.next()
+        "streak" => {
+            let streak_val = cont.next()
                 .unwrap()
                 .parse::<u32>()
-                .map_err(|_| SimpleRuleParseError::IncorrectContent)?),
-        "every" => SimpleRuleSeq::Selected(match cont.next().unwrap() {
-            "DAY" => 0,
-            "MONDAY" => 1,
-            "TUESDAY" => 2,
-            "WEDNESDAY" => 3,
-            "THURSDAY" => 4,
-            "FRIDAY" => 5,
-            "SATURDAY" => 6,
-            "SUNDAY" => 7,
-            raw_val @ _ => {
-                let val = raw_val.parse::<u8>()
-                    .map_err(|_| SimpleRuleParseError::IncorrectContent)?;
-                if val > 7 {
-                    Err(SimpleRuleParseError::IncorrectContent)?
-                } else {
-                    val
-                }
+                .map_err(|_| SimpleRuleParseError::IncorrectContent)?;
+            match raw.skipped {
+                Some(name) => SimpleRuleSeq::SkippingStreak(streak_val, name.to_owned(), None),
+                None => SimpleRuleSeq::Streak(streak_val)
+            }
+        }
+        "in" => SimpleRuleSeq::In(parse_calendar_name(cont)?, None),
+        "not_in" => SimpleRuleSeq::NotIn(parse_calendar_name(cont)?, None),
+        "every" => {
+            let selected = cont.next().unwrap();
+            if let Some(nth) = parse_nth(selected) {
+                SimpleRuleSeq::Nth(nth?, 0)
+            } else {
+                SimpleRuleSeq::Selected(parse_selected(selected)?)
+            }
+        }
+        "best" if raw.seq.is_none() => SimpleRuleSeq::Best(raw.group.map(str::to_owned)),
+        "improved" => SimpleRuleSeq::Improved(raw.group.map(str::to_owned)),
+        "contains" | "contains_all" => SimpleRuleSeq::ContainsAll,
+        "contains_any" => SimpleRuleSeq::ContainsAny,
+        "size" => SimpleRuleSeq::Size,
+        _ => match parse_stat(seq) {
+            Some(stat) => SimpleRuleSeq::Stat(stat, parse_window(raw.window)?, Vec::new()),
+            None => return Err(SimpleRuleParseError::UnsupportedSeq)
+        }
+    };
+    let windowed = matches!(res, SimpleRuleSeq::Stat(..));
+    let grouped = matches!(res, SimpleRuleSeq::Best(_) | SimpleRuleSeq::Improved(_));
+    let skipping = matches!(res, SimpleRuleSeq::SkippingStreak(..));
+    if (raw.window.is_some() && !windowed) || (raw.group.is_some() && !grouped)
+        || (raw.skipped.is_some() && !skipping) {
+        return Err(SimpleRuleParseError::IncorrectContent);
+    }
+    Ok(res)
+}
+
+fn parse_calendar_name<'a>(cont: &mut impl Iterator<Item = &'a str>) -> Result<String, SimpleRuleParseError> {
+    let name = cont.next().unwrap();
+    let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid && cont.next().is_none() {
+        Ok(name.to_owned())
+    } else {
+        Err(SimpleRuleParseError::IncorrectContent)
+    }
+}
+
+fn parse_selected(input: &str) -> Result<u8, SimpleRuleParseError> {
+    let res = match input {
+        "DAY" => 0,
+        "MONDAY" => 1,
+        "TUESDAY" => 2,
+        "WEDNESDAY" => 3,
+        "THURSDAY" => 4,
+        "FRIDAY" => 5,
+        "SATURDAY" => 6,
+        "SUNDAY" => 7,
+        raw_val => {
+            let val = raw_val.parse::<u8>()
+                .map_err(|_| SimpleRuleParseError::IncorrectContent)?;
+            if val > 7 {
+                Err(SimpleRuleParseError::IncorrectContent)?
+            } else {
+                val
             }
-        }),
-        _ => return Err(SimpleRuleParseError::UnsupportedSeq)
+        }
     };
     Ok(res)
-}
\ No newline at end of file
+}
+
+// ordinals such as 1st, 2nd, 3rd or 10th
+fn parse_nth(input: &str) -> Option<Result<u32, SimpleRuleParseError>> {
+    let num = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| input.strip_suffix(suffix))?;
+    let res = match num.parse::<u32>() {
+        Ok(0) | Err(_) => Err(SimpleRuleParseError::IncorrectContent),
+        Ok(val) => Ok(val)
+    };
+    Some(res)
+}
+
+fn parse_stat(seq: &str) -> Option<SimpleRuleStat> {
+    let res = match seq {
+        "avg" => SimpleRuleStat::Avg,
+        "max" => SimpleRuleStat::Max,
+        "min" => SimpleRuleStat::Min,
+        "median" => SimpleRuleStat::Median,
+        _ => return None
+    };
+    Some(res)
+}
+
+fn parse_window(window: Option<&str>) -> Result<u32, SimpleRuleParseError> {
+    let window = window
+        .ok_or(SimpleRuleParseError::IncorrectContent)?
+        .parse::<u32>()
+        .map_err(|_| SimpleRuleParseError::IncorrectContent)?;
+    if window == 0 {
+        Err(SimpleRuleParseError::IncorrectContent)
+    } else {
+        Ok(window)
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 pub enum SimpleRuleParseError {
-    NoMatch, UnsupportedSeq, IncorrectContent
+    NoMatch, UnsupportedSeq, IncorrectContent, OptionalWithoutStrict
 }
 
 impl Debug for SimpleRuleParseError {
@@ -17,7 +17,8 @@ impl Display for SimpleRuleParseError {
         let msg = match self {
             SimpleRuleParseError::NoMatch => "couldn't match input with the rule",
             SimpleRuleParseError::UnsupportedSeq => "provided unsupported seq qualifier",
-            SimpleRuleParseError::IncorrectContent => "one of the content value is incorrect"
+            SimpleRuleParseError::IncorrectContent => "one of the content value is incorrect",
+            SimpleRuleParseError::OptionalWithoutStrict => "optional parts only make sense in strict rules"
         };
         f.write_str(msg)
     }
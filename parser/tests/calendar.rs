@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use common::calendar::{Calendar, NamedCalendar};
+use common::simple_rule::{DatePattern, SimpleRuleBorder, SimpleRulePartValue};
+use parser::parsing::calendar::error::CalendarParseError;
+
+fn parse_calendar(s: &str) -> Result<NamedCalendar, CalendarParseError> {
+    parser::parse_calendar(s)
+}
+
+#[test]
+fn name_correct() {
+    let parsed = parse_calendar("calendar exams = 2025.01.20").unwrap();
+    assert_eq!(parsed.name, "exams")
+}
+
+#[test]
+fn name_incorrect() {
+    let parsed = parse_calendar("calendar = 2025.01.20");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn ranges() {
+    let parsed = parse_calendar("calendar exams = 2025.01.20..2025.02.05, 2025.06.10..2025.06.30").unwrap();
+    let expected = Calendar::new(vec!(
+        SimpleRulePartValue::new(
+            SimpleRuleBorder::Between,
+            NaiveDate::from_str("2025-01-20").unwrap(),
+            Some(NaiveDate::from_str("2025-02-05").unwrap())
+        ),
+        SimpleRulePartValue::new(
+            SimpleRuleBorder::Between,
+            NaiveDate::from_str("2025-06-10").unwrap(),
+            Some(NaiveDate::from_str("2025-06-30").unwrap())
+        )
+    ));
+    assert_eq!(parsed.calendar, expected)
+}
+
+#[test]
+fn patterns() {
+    let parsed = parse_calendar("calendar holidays = *.12.25,2025.04.18").unwrap();
+    let expected = Calendar::new(vec!(
+        SimpleRulePartValue::recurring(DatePattern { year: None, month: Some(12), day: Some(25) }, None),
+        SimpleRulePartValue::exact(NaiveDate::from_str("2025-04-18").unwrap())
+    ));
+    assert_eq!(parsed.calendar, expected)
+}
+
+#[test]
+fn date_incorrect() {
+    let parsed = parse_calendar("calendar exams = 2025.13.20");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn empty_incorrect() {
+    let parsed = parse_calendar("calendar exams = ");
+    assert!(parsed.is_err())
+}
\ No newline at end of file
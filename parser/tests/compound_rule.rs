@@ -1,5 +1,6 @@
-use chrono::NaiveDate;
-use common::compound_rule::{CompoundRule, CompoundRuleSeq, NamedCompoundRule};
+use chrono::{NaiveDate, Weekday};
+use common::compound_rule::{CompoundRule, CompoundRuleGap, CompoundRuleSeq, NamedCompoundRule};
+use common::period::{ActiveWindow, ResetPeriod};
 use parser::parsing::compound_rule::error::CompoundRuleParseError;
 
 fn parse_named_rule(s: &str) -> Result<NamedCompoundRule, CompoundRuleParseError> {
@@ -36,6 +37,30 @@ fn repeat() {
     assert_eq!(parsed.iterations, -1)
 }
 
+#[test]
+fn repeat_count() {
+    let parsed = parse_rule("repeat 3 every 2 any arg1");
+    assert_eq!((parsed.iterations, parsed.every), (3, 2))
+}
+
+#[test]
+fn repeat_count_zero() {
+    let parsed = parse_named_rule("n: repeat 0 any arg1");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn repeat_count_overflow() {
+    let parsed = parse_named_rule("n: repeat 3000000000 any arg1");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn locked() {
+    let parsed = parse_rule("locked repeat 2 any arg1");
+    assert_eq!((parsed.locked, parsed.iterations), (true, 2))
+}
+
 #[test]
 fn no_every() {
     let parsed = parse_rule("any arg1");
@@ -84,12 +109,91 @@ fn seq_seq() {
     assert_eq!(parsed.seq, CompoundRuleSeq::Order)
 }
 
+#[test]
+fn seq_strictseq() {
+    let parsed = parse_rule("strictseq arg1 arg2");
+    assert_eq!((parsed.seq, parsed.gap), (CompoundRuleSeq::StrictOrder, None))
+}
+
+#[test]
+fn gap_events() {
+    let parsed = parse_rule("strictseq arg1 arg2 gap 2");
+    assert_eq!(parsed.gap, Some(CompoundRuleGap::Events(2)))
+}
+
+#[test]
+fn gap_days() {
+    let parsed = parse_rule("seq arg1 arg2 gap 1d");
+    assert_eq!(parsed.gap, Some(CompoundRuleGap::Days(1)))
+}
+
+#[test]
+fn gap_zero_events_incorrect() {
+    let parsed = parse_named_rule("n: strictseq arg1 arg2 gap 0");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn gap_no_seq_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 arg2 gap 2");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn seq_streak_correct() {
     let parsed = parse_rule("streak 2 arg1 arg2");
     assert_eq!(parsed.seq, CompoundRuleSeq::Streak(2, vec!(NaiveDate::default(), NaiveDate::default())))
 }
 
+#[test]
+fn seq_daily_streak_all() {
+    let parsed = parse_rule("streak 2 all arg1 arg2 arg3");
+    let expected = CompoundRuleSeq::DailyStreak(2, 3, vec!(NaiveDate::default(), NaiveDate::default()), vec!());
+    assert_eq!(parsed.seq, expected)
+}
+
+#[test]
+fn seq_daily_streak_atleast() {
+    let parsed = parse_rule("streak 5 atleast 2 arg1 arg2 arg3");
+    assert!(matches!(parsed.seq, CompoundRuleSeq::DailyStreak(5, 2, ..)))
+}
+
+#[test]
+fn seq_daily_streak_atleast_too_many() {
+    let parsed = parse_named_rule("n: streak 5 atleast 4 arg1 arg2 arg3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn seq_daily_streak_prefixed_arg() {
+    let parsed = parse_rule("streak 2 allowed arg2");
+    assert_eq!(
+        (parsed.seq, parsed.values),
+        (
+            CompoundRuleSeq::Streak(2, vec!(NaiveDate::default(), NaiveDate::default())),
+            vec!("allowed".to_owned(), "arg2".to_owned())
+        )
+    )
+}
+
+#[test]
+fn seq_any_keeps_all_arg() {
+    let parsed = parse_rule("any all b");
+    assert_eq!(
+        (parsed.seq, parsed.values),
+        (CompoundRuleSeq::Any, vec!("all".to_owned(), "b".to_owned()))
+    )
+}
+
+#[test]
+fn seq_order_keeps_atleast_args() {
+    let parsed = parse_rule("seq atleast 2 a b c");
+    assert_eq!(
+        (parsed.seq, parsed.values),
+        (CompoundRuleSeq::Order, vec!("atleast".to_owned(), "2".to_owned(), "a".to_owned(), "b".to_owned(), "c".to_owned()))
+    )
+}
+
 #[test]
 fn seq_streak_incorrect() {
     let parsed = parse_named_rule("n: streak arg1 arg2");
@@ -102,6 +206,101 @@ fn seq_streak_incorrect2() {
     assert!(parsed.is_err())
 }
 
+#[test]
+fn seq_atleast_correct() {
+    let parsed = parse_rule("atleast 3 arg1 arg2 arg3 arg4 arg5");
+    assert_eq!(parsed.seq, CompoundRuleSeq::AtLeast(3))
+}
+
+#[test]
+fn seq_atleast_incorrect() {
+    let parsed = parse_named_rule("n: atleast arg1 arg2");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn seq_atleast_too_many() {
+    let parsed = parse_named_rule("n: atleast 3 arg1 arg2");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn no_within() {
+    let parsed = parse_rule("all arg1 arg2");
+    assert_eq!(parsed.within, None)
+}
+
+#[test]
+fn within() {
+    let parsed = parse_rule("all arg1 arg2 within 3d");
+    assert_eq!((parsed.within, parsed.values), (Some(3), vec!("arg1".to_owned(), "arg2".to_owned())))
+}
+
+#[test]
+fn within_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 arg2 within 0d");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn within_no_unit_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 arg2 within 3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn within_streak_incorrect() {
+    let parsed = parse_named_rule("n: streak 2 arg1 within 3d");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn within_prefixed_arg() {
+    let parsed = parse_rule("all within_week arg2");
+    assert_eq!(parsed.values, vec!("within_week".to_owned(), "arg2".to_owned()))
+}
+
+#[test]
+fn without() {
+    let parsed = parse_rule("solved without failed");
+    assert_eq!(
+        (parsed.seq, parsed.values, parsed.without, parsed.without_window),
+        (CompoundRuleSeq::Any, vec!("solved".to_owned()), vec!("failed".to_owned()), None)
+    )
+}
+
+#[test]
+fn without_window() {
+    let parsed = parse_rule("all arg1 arg2 without arg3 arg4 for 7d within 3d");
+    assert_eq!(
+        (parsed.values, parsed.without, parsed.without_window, parsed.within),
+        (
+            vec!("arg1".to_owned(), "arg2".to_owned()),
+            vec!("arg3".to_owned(), "arg4".to_owned()),
+            Some(7),
+            Some(3)
+        )
+    )
+}
+
+#[test]
+fn without_window_incorrect() {
+    let parsed = parse_named_rule("n: arg1 without arg2 for 0d");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn without_overlap_incorrect() {
+    let parsed = parse_named_rule("n: any arg1 arg2 without arg2");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn without_no_seq_multiple_incorrect() {
+    let parsed = parse_named_rule("n: arg1 arg2 without arg3");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn arg() {
     let parsed = parse_rule("any arg");
@@ -133,6 +332,52 @@ fn incorrect_arg() {
     assert!(parsed.is_err())
 }
 
+#[test]
+fn resets() {
+    let parsed = parse_rule("all arg1 arg2 resets weekly(monday)");
+    assert_eq!((parsed.periods.resets, parsed.values.len()), (Some(ResetPeriod::Weekly(Weekday::Mon)), 2))
+}
+
+#[test]
+fn resets_after_within() {
+    let parsed = parse_rule("all arg1 arg2 within 3d resets daily");
+    assert_eq!((parsed.periods.resets, parsed.within), (Some(ResetPeriod::Daily), Some(3)))
+}
+
+#[test]
+fn resets_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 resets yearly");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn expires() {
+    let parsed = parse_rule("all arg1 arg2 expires 14d resets monthly");
+    assert_eq!((parsed.periods.expires, parsed.periods.resets), (Some(14), Some(ResetPeriod::Monthly)))
+}
+
+#[test]
+fn expires_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 arg2 expires 14");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn active() {
+    let parsed = parse_rule("all arg1 arg2 resets daily active 2025.03.01..2025.03.31");
+    let expected = ActiveWindow::new(
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 3, 31).unwrap()
+    );
+    assert_eq!(parsed.active, Some(expected))
+}
+
+#[test]
+fn active_incorrect() {
+    let parsed = parse_named_rule("n: all arg1 arg2 active 2025.03.01");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn rule() {
     let parsed = parse_named_rule("ex_1: repeat every 3 streak 3 arg_1 arg_2 arg_3");
@@ -35,6 +35,18 @@ fn part_repeat() {
     assert_eq!(parsed.values.first().unwrap().iterations, -1);
 }
 
+#[test]
+fn part_repeat_count() {
+    let parsed = parse_res("repeat 3 msg Hi");
+    assert_eq!(parsed.values.first().unwrap().iterations, 3);
+}
+
+#[test]
+fn part_repeat_count_zero() {
+    let parsed = parse_named_res("n -> repeat 0 msg Hi");
+    assert!(parsed.is_err());
+}
+
 #[test]
 fn part_incorrect_kind() {
     let parsed = parse_named_res("n -> cookie Hi");
@@ -71,6 +83,30 @@ fn part_restart_kind() {
     assert_eq!(parsed.values.first().unwrap().kind, RuleResultKind::Restart);
 }
 
+#[test]
+fn part_disable_kind() {
+    let parsed = parse_res("disable rule");
+    assert_eq!(parsed.values.first().unwrap().kind, RuleResultKind::Disable);
+}
+
+#[test]
+fn part_reset_kind() {
+    let parsed = parse_res("reset rule");
+    assert_eq!(parsed.values.first().unwrap().kind, RuleResultKind::Reset);
+}
+
+#[test]
+fn part_unlock_kind() {
+    let parsed = parse_res("unlock rule");
+    assert_eq!(parsed.values.first().unwrap().kind, RuleResultKind::Unlock);
+}
+
+#[test]
+fn part_lock_kind() {
+    let parsed = parse_res("lock rule");
+    assert_eq!(parsed.values.first().unwrap().kind, RuleResultKind::Lock);
+}
+
 #[test]
 fn part_incorrect_seq() {
     let parsed = parse_named_res("n -> cookie Hi Hello");
@@ -145,4 +181,29 @@ fn multiple_parts() {
         RuleResultValue::new(-1, RuleResultKind::Offer, RuleResultSeq::Random, vec!("1pkt".to_owned(), "badge".to_owned(), "emote".to_owned())),
     )));
     assert_eq!(parsed.unwrap(), expected)
+}
+
+#[test]
+fn quoted_arg() {
+    let parsed = parse_res("msg \"You scored {achieving} on exercise {of}!\" Hi");
+    assert_eq!(parsed.values.first().unwrap().values, vec!("You scored {achieving} on exercise {of}!", "Hi"));
+}
+
+#[test]
+fn quoted_arg_with_separator() {
+    let parsed = parse_named_res("n -> msg \"Done; well played\"; reward xp 10").unwrap();
+    assert_eq!((parsed.res.values.len(), parsed.res.values[0].values.clone()), (2, vec!("Done; well played".to_owned())));
+}
+
+#[test]
+fn quoted_arg_unterminated() {
+    let parsed = parse_named_res("n -> msg \"Done");
+    assert!(parsed.is_err());
+}
+
+#[test]
+fn part_reward_pairs() {
+    let parsed = parse_res("reward gold_badge xp 50 coins");
+    let value = parsed.values.first().unwrap();
+    assert_eq!((value.kind.clone(), value.values.len()), (RuleResultKind::Reward, 4));
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
-use chrono::{NaiveDate, NaiveTime};
-use common::simple_rule::{NamedSimpleRule, SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, WrappedSimpleRulePart};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use common::event::{Duration, ListItem};
+use common::period::{ActiveWindow, ResetPeriod};
+use common::simple_rule::{DatePattern, NamedSimpleRule, SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, SimpleRuleStat, WrappedSimpleRulePart};
 
 use parser::parsing::simple_rule::error::SimpleRuleParseError;
 
@@ -91,6 +93,33 @@ fn action_incorrect() {
     assert!(parsed.is_err())
 }
 
+#[test]
+fn action_nth() {
+    let parsed = parse_part("did", "every(10th)");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Nth(10, 0), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn action_nth_numbers() {
+    let parsed = parse_part("did", "every(2nd,3,4)");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Nth(2, 0), vec!(
+            SimpleRulePartValue::exact(3),
+            SimpleRulePartValue::exact(4)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn action_nth_incorrect() {
+    let parsed = parse_rule("n: did every(0th)");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn objectlike_asterisk() {
     let parsed = parse_part("with", "*");
@@ -475,6 +504,60 @@ fn date_all_dates() {
     assert_eq!(parsed, expected)
 }
 
+#[test]
+fn date_pattern_yearly() {
+    let parsed = parse_part("on", "*.12.24");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::recurring(DatePattern { year: None, month: Some(12), day: Some(24) }, None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_pattern_monthly() {
+    let parsed = parse_part("on", "*.*.01");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::recurring(DatePattern { year: None, month: None, day: Some(1) }, None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_pattern_range() {
+    let parsed = parse_part("on", "*.12.20..*.01.06");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::recurring(
+                DatePattern { year: None, month: Some(12), day: Some(20) },
+                Some(DatePattern { year: None, month: Some(1), day: Some(6) })
+            )
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_pattern_range_mismatched_incorrect() {
+    let parsed = parse_rule("n: on *.12.20..*.*.06");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_pattern_range_year_incorrect() {
+    let parsed = parse_rule("n: on 2000.*.01..2000.*.05");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_pattern_incorrect() {
+    let parsed = parse_rule("n: on *.13.01");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn date_streak() {
     let parsed = parse_part("on", "streak(3)");
@@ -488,6 +571,78 @@ fn date_streak() {
     assert_eq!(parsed, expected)
 }
 
+#[test]
+fn date_skipping_streak() {
+    let parsed = parse_part("on", "streak(2) skipping holidays");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::SkippingStreak(2, "holidays".to_string(), None), vec!(
+            SimpleRulePartValue::exact(NaiveDate::default()),
+            SimpleRulePartValue::exact(NaiveDate::default())
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_skipping_no_streak_incorrect() {
+    let parsed = parse_rule("n: on all(2000.01.01) skipping holidays");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_in() {
+    let parsed = parse_part("on", "in(exams)");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::In("exams".to_string(), None), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_not_in() {
+    let parsed = parse_part("on", "not_in(holidays)");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::NotIn("holidays".to_string(), None), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn date_in_multiple_incorrect() {
+    let parsed = parse_rule("n: on in(exams,holidays)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn player_in_incorrect() {
+    let parsed = parse_rule("n: player in(exams)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn took_streak_incorrect() {
+    let parsed = parse_rule("n: took streak(3)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn took_selected_incorrect() {
+    let parsed = parse_rule("n: took every(MONDAY)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn time_streak_incorrect() {
+    let parsed = parse_rule("n: at streak(3)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn player_selected_incorrect() {
+    let parsed = parse_rule("n: player every(3)");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn date_streak_incorrect() {
     let parsed = parse_rule("n: on streak(0)");
@@ -524,6 +679,15 @@ fn date_selected_text_incorrect() {
     assert!(parsed.is_err())
 }
 
+#[test]
+fn date_nth() {
+    let parsed = parse_part("on", "every(3rd)");
+    let expected = WrappedSimpleRulePart::Date(
+        SimpleRulePart::new(SimpleRuleSeq::Nth(3, 0), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
 #[test]
 fn date_incorrect() {
     let parsed = parse_rule("n: on 2000-01-01");
@@ -758,6 +922,296 @@ fn result_mixed() {
     assert_eq!(parsed, expected)
 }
 
+#[test]
+fn result_modulo() {
+    let parsed = parse_part("achieving", "%5==2");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Modulo, 5, Some(2))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_modulo_no_remainder() {
+    let parsed = parse_part("achieving", "%10");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Modulo, 10, Some(0))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_modulo_incorrect() {
+    let parsed = parse_rule("n: achieving %5==x");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_modulo_zero_step() {
+    let parsed = parse_rule("n: achieving %0");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_modulo_offset_too_big() {
+    let parsed = parse_rule("n: achieving %5==7");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn time_modulo_incorrect() {
+    let parsed = parse_rule("n: at %5==0");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn duration_modulo_incorrect() {
+    let parsed = parse_rule("n: took %60");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn number_pattern_incorrect() {
+    let parsed = parse_rule("n: player *.12.24");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_avg() {
+    let parsed = parse_part("achieving", "avg(>=80) last 5");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Stat(SimpleRuleStat::Avg, 5, vec!()), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 80, None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_median() {
+    let parsed = parse_part("achieving", "median(50..100) last 3");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Stat(SimpleRuleStat::Median, 3, vec!()), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Between, 50, Some(100))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_stat_no_window() {
+    let parsed = parse_rule("n: achieving max(>90)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_stat_zero_window() {
+    let parsed = parse_rule("n: achieving min(>90) last 0");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_window_no_stat() {
+    let parsed = parse_rule("n: achieving all(>90) last 3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_best() {
+    let parsed = parse_part("achieving", "best");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Best(None), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_best_grouped() {
+    let parsed = parse_part("achieving", "best by of");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Best(Some("of".to_string())), vec!())
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_improved() {
+    let parsed = parse_part("achieving", "improved");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Improved(None), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Greater, 0, None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_improved_margin() {
+    let parsed = parse_part("achieving", "improved(>=10) by of");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Improved(Some("of".to_string())), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 10, None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn result_group_no_history() {
+    let parsed = parse_rule("n: achieving 100 by of");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn result_improved_window_incorrect() {
+    let parsed = parse_rule("n: achieving improved(>=10) last 3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_best_incorrect() {
+    let parsed = parse_rule("n: on best");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_stat_incorrect() {
+    let parsed = parse_rule("n: on avg(2000.01.01) last 3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn took_less() {
+    let parsed = parse_part("took", "<5m");
+    let expected = WrappedSimpleRulePart::Duration(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Less, Duration(300), None)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn took_between() {
+    let parsed = parse_part("took", "30s..2h");
+    let expected = WrappedSimpleRulePart::Duration(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Between, Duration(30), Some(Duration(7200)))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn took_combined_units() {
+    let parsed = parse_part("took", "1d1h30m15s");
+    let expected = WrappedSimpleRulePart::Duration(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::exact(Duration(91815))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn took_seconds() {
+    let parsed = parse_part("took", "45");
+    let expected = WrappedSimpleRulePart::Duration(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::exact(Duration(45))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn took_unit_incorrect() {
+    let parsed = parse_rule("n: took <5w");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn took_missing_amount_incorrect() {
+    let parsed = parse_rule("n: took <m");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn took_stat_incorrect() {
+    let parsed = parse_rule("n: took avg(<5m) last 3");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn tags_contains() {
+    let parsed = parse_part("tags", "contains(for_loop)");
+    let expected = WrappedSimpleRulePart::List(
+        SimpleRulePart::new(SimpleRuleSeq::ContainsAll, vec!(
+            SimpleRulePartValue::exact(ListItem::Text("for_loop".to_string()))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn tags_contains_all() {
+    let parsed = parse_part("passed", "contains_all(1,2,edge_case)");
+    let expected = WrappedSimpleRulePart::List(
+        SimpleRulePart::new(SimpleRuleSeq::ContainsAll, vec!(
+            SimpleRulePartValue::exact(ListItem::Number(1)),
+            SimpleRulePartValue::exact(ListItem::Number(2)),
+            SimpleRulePartValue::exact(ListItem::Text("edge_case".to_string()))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn tags_contains_any() {
+    let parsed = parse_part("tags", "contains_any(loops,recursion)");
+    let expected = WrappedSimpleRulePart::List(
+        SimpleRulePart::new(SimpleRuleSeq::ContainsAny, vec!(
+            SimpleRulePartValue::exact(ListItem::Text("loops".to_string())),
+            SimpleRulePartValue::exact(ListItem::Text("recursion".to_string()))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn tags_contains_empty_item_incorrect() {
+    let parsed = parse_rule("n: tags contains(loops,,recursion)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn tags_size() {
+    let parsed = parse_part("tags", "size(>=3,1)");
+    let expected = WrappedSimpleRulePart::List(
+        SimpleRulePart::new(SimpleRuleSeq::Size, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, ListItem::Number(3), None),
+            SimpleRulePartValue::exact(ListItem::Number(1))
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn tags_size_incorrect() {
+    let parsed = parse_rule("n: tags size(>many)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn date_contains_incorrect() {
+    let parsed = parse_rule("n: on contains(2000.01.01)");
+    assert!(parsed.is_err())
+}
+
 #[test]
 fn repeat_number() {
     let parsed = parse_rule("n: repeat 3 player 1").unwrap();
@@ -788,6 +1242,159 @@ fn repeat_infite() {
     assert_eq!(parsed.rule.iterations, -1)
 }
 
+#[test]
+fn keyword_underscore() {
+    let parsed = parse_part("week_of_year", "5");
+    let expected = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::exact(5)
+        ))
+    );
+    assert_eq!(parsed, expected)
+}
+
+#[test]
+fn strict_none() {
+    let parsed = parse_rule("n: player 1").unwrap();
+    assert!(!parsed.rule.strict)
+}
+
+#[test]
+fn strict() {
+    let parsed = parse_rule("n: strict player 1").unwrap();
+    assert!(parsed.rule.strict)
+}
+
+#[test]
+fn optional_part() {
+    let parsed = parse_rule("n: strict player 1 optional at 10:00").unwrap();
+    assert_eq!(parsed.rule.optional.into_iter().collect::<Vec<_>>(), vec!("at".to_string()))
+}
+
+#[test]
+fn optional_without_strict_incorrect() {
+    let parsed = parse_rule("n: player 1 optional at 10:00");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn optional_repeat_incorrect() {
+    let parsed = parse_rule("n: player 1 optional repeat 2");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn category_none() {
+    let parsed = parse_rule("n: player 1").unwrap();
+    assert_eq!(parsed.rule.category, None)
+}
+
+#[test]
+fn category() {
+    let parsed = parse_rule("n: when video_watched: strict player 1").unwrap();
+    assert_eq!((parsed.rule.category, parsed.rule.strict), (Some("video_watched".to_string()), true))
+}
+
+#[test]
+fn locked() {
+    let parsed = parse_rule("n: when submission: locked strict player 1").unwrap();
+    assert_eq!((parsed.rule.locked, parsed.rule.strict), (true, true))
+}
+
+#[test]
+fn not_locked() {
+    let parsed = parse_rule("n: player 1").unwrap();
+    assert!(!parsed.rule.locked)
+}
+
+#[test]
+fn category_keyword() {
+    let parsed = parse_rule("n: when 1").unwrap();
+    assert_eq!((parsed.rule.category, parsed.rule.parts.len()), (None, 1))
+}
+
+#[test]
+fn resets_daily() {
+    let parsed = parse_rule("n: player 1 resets daily").unwrap();
+    assert_eq!((parsed.rule.periods.resets, parsed.rule.parts.len()), (Some(ResetPeriod::Daily), 1))
+}
+
+#[test]
+fn resets_weekly_day() {
+    let parsed = parse_rule("n: player 1 resets weekly(SUNDAY)").unwrap();
+    assert_eq!(parsed.rule.periods.resets, Some(ResetPeriod::Weekly(Weekday::Sun)))
+}
+
+#[test]
+fn resets_leading_incorrect() {
+    let parsed = parse_rule("n: resets weekly(SUNDAY) player 1");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn resets_monthly() {
+    let parsed = parse_rule("n: player 1 resets monthly").unwrap();
+    assert_eq!(parsed.rule.periods.resets, Some(ResetPeriod::Monthly))
+}
+
+#[test]
+fn resets_incorrect() {
+    let parsed = parse_rule("n: player 1 resets weekly(someday)");
+    assert!(parsed.is_err())
+}
+
+#[test]
+fn expires() {
+    let parsed = parse_rule("n: did all(1,2) expires 14d").unwrap();
+    assert_eq!((parsed.rule.periods.expires, parsed.rule.parts.len()), (Some(14), 1))
+}
+
+#[test]
+fn expires_incorrect() {
+    let zero = parse_rule("n: did all(1,2) expires 0d");
+    let wrong_order = parse_rule("n: did all(1,2) resets daily expires 7d");
+    assert!(zero.is_err() && wrong_order.is_err())
+}
+
+#[test]
+fn expires_part() {
+    let parsed = parse_rule("n: did all(1,2) expires 14").unwrap();
+    assert_eq!((parsed.rule.periods.expires, parsed.rule.parts.len()), (None, 2))
+}
+
+#[test]
+fn active() {
+    let parsed = parse_rule("n: player 1 active 2025.03.01..2025.03.31").unwrap();
+    let expected = ActiveWindow::new(
+        NaiveDate::from_str("2025-03-01").unwrap(),
+        NaiveDate::from_str("2025-03-31").unwrap()
+    );
+    assert_eq!((parsed.rule.active, parsed.rule.parts.len()), (Some(expected), 1))
+}
+
+#[test]
+fn active_incorrect() {
+    let reversed = parse_rule("n: player 1 active 2025.03.31..2025.03.01");
+    let single = parse_rule("n: player 1 active 2025.03.01");
+    let followed = parse_rule("n: player 1 active 2025.03.01..2025.03.31 did 1");
+    assert!(reversed.is_err() && single.is_err() && followed.is_err())
+}
+
+#[test]
+fn all_clauses() {
+    let parsed = parse_rule("n: did all(1,2) expires 3d resets weekly active 2025.03.01..2025.03.31").unwrap();
+    assert_eq!(
+        (parsed.rule.periods.expires, parsed.rule.periods.resets, parsed.rule.active.is_some(), parsed.rule.parts.len()),
+        (Some(3), Some(ResetPeriod::Weekly(Weekday::Mon)), true, 1)
+    )
+}
+
+#[test]
+fn active_part() {
+    let parsed = parse_rule("n: active 1").unwrap();
+    assert_eq!((parsed.rule.active, parsed.rule.parts.contains_key("active")), (None, true))
+}
+
 #[test]
 fn rule() {
     let parsed = parse_rule("name: player 1 did 2 with 3 in 4 of 5 on 2000.01.01 at 12:00 achieving 100 repeat 1");
@@ -1,10 +1,19 @@
 use std::collections::HashMap;
+use common::calendar::{Calendar, NamedCalendar};
 use common::compound_rule::{CompoundRule, NamedCompoundRule};
 use common::event::Event;
-use common::rule_result::{NamedRuleResult, RuleResult, RuleResultKind};
+use common::rule_result::{NamedRuleResult, ResultRecord, RuleResult, RuleType};
 use common::simple_rule::{NamedSimpleRule, SimpleRule};
 use serde::{Deserialize, Serialize};
+use crate::core::clock::Clock;
+use crate::core::enriching::EventEnricher;
+use crate::core::error::ProcessingError;
+use crate::core::ledger::Ledger;
 
+pub mod clock;
+pub mod enriching;
+pub mod error;
+pub mod ledger;
 mod processing;
 
 #[derive(Serialize, Deserialize)]
@@ -15,27 +24,93 @@ pub struct State {
     pub disabled_simple_rules: HashMap<String, SimpleRule>,
     pub disabled_compound_rules: HashMap<String, CompoundRule>,
     pub disabled_rule_results: HashMap<String, RuleResult>,
+    // currencies granted by typed rewards
+    #[serde(default)]
+    pub ledger: Ledger,
+    // how many times each rule has been completed
+    #[serde(default)]
+    pub completions: HashMap<String, u32>,
+    // rules waiting for an unlock result
+    #[serde(default)]
+    pub locked_simple_rules: HashMap<String, SimpleRule>,
+    #[serde(default)]
+    pub locked_compound_rules: HashMap<String, CompoundRule>,
+    // rules whose active window hasn't started yet, along with their results
+    #[serde(default)]
+    pub pending_simple_rules: HashMap<String, SimpleRule>,
+    #[serde(default)]
+    pub pending_compound_rules: HashMap<String, CompoundRule>,
+    #[serde(default)]
+    pub pending_rule_results: HashMap<String, RuleResult>,
+    // rules whose active window is over, along with their results
+    #[serde(default)]
+    pub expired_simple_rules: HashMap<String, SimpleRule>,
+    #[serde(default)]
+    pub expired_compound_rules: HashMap<String, CompoundRule>,
+    #[serde(default)]
+    pub expired_rule_results: HashMap<String, RuleResult>,
+    // derivations are code, so they're configured again after loading
+    #[serde(skip)]
+    pub enricher: EventEnricher,
+    // fallback for events without a date, configured again after loading as well
+    #[serde(skip)]
+    pub clock: Option<Box<dyn Clock>>
 }
 
 impl State {
     pub fn new(simple_rules: Vec<NamedSimpleRule>,
            compound_rules: Vec<NamedCompoundRule>,
            rule_results: Vec<NamedRuleResult>) -> Self {
+        let (locked_simple_rules, simple_rules): (HashMap<_, _>, HashMap<_, _>) = simple_rules.into_iter()
+            .map(|v| (v.name, v.rule))
+            .partition(|(_, r)| r.locked);
+        let (locked_compound_rules, compound_rules): (HashMap<_, _>, HashMap<_, _>) = compound_rules.into_iter()
+            .map(|v| (v.name, v.rule))
+            .partition(|(_, r)| r.locked);
+        // rules with an active window wait for the first dated event
+        let (pending_simple_rules, enabled_simple_rules): (HashMap<_, _>, HashMap<_, _>) = simple_rules.into_iter()
+            .partition(|(_, r)| r.active.is_some());
+        let (pending_compound_rules, enabled_compound_rules): (HashMap<_, _>, HashMap<_, _>) = compound_rules.into_iter()
+            .partition(|(_, r)| r.active.is_some());
+        let (pending_rule_results, enabled_rule_results) = rule_results.into_iter()
+            .map(|v| (v.name, v.res))
+            .partition(|(name, _)| pending_simple_rules.contains_key(name) || pending_compound_rules.contains_key(name));
         Self {
-            enabled_simple_rules: simple_rules.into_iter()
-                .map(|v| (v.name, v.rule))
-                .collect(),
-            enabled_compound_rules: compound_rules.into_iter()
-                .map(|v| (v.name, v.rule))
-                .collect(),
-            enabled_rule_results: rule_results.into_iter()
-                .map(|v| (v.name, v.res))
-                .collect(),
+            enabled_simple_rules,
+            enabled_compound_rules,
+            enabled_rule_results,
             disabled_simple_rules: HashMap::new(),
             disabled_compound_rules: HashMap::new(),
-            disabled_rule_results: HashMap::new()
+            disabled_rule_results: HashMap::new(),
+            ledger: Ledger::default(),
+            completions: HashMap::new(),
+            locked_simple_rules,
+            locked_compound_rules,
+            pending_simple_rules,
+            pending_compound_rules,
+            pending_rule_results,
+            expired_simple_rules: HashMap::new(),
+            expired_compound_rules: HashMap::new(),
+            expired_rule_results: HashMap::new(),
+            enricher: EventEnricher::default(),
+            clock: None
         }
     }
+    // calendars are copied into the rules referencing them, so they're saved along with them
+    pub fn link_calendars(&mut self, calendars: Vec<NamedCalendar>) -> Result<(), String> {
+        let calendars: HashMap<String, Calendar> = calendars.into_iter()
+            .map(|v| (v.name, v.calendar))
+            .collect();
+        self.enabled_simple_rules
+            .values_mut()
+            .chain(self.disabled_simple_rules.values_mut())
+            .chain(self.pending_simple_rules.values_mut())
+            .chain(self.locked_simple_rules.values_mut())
+            .try_for_each(|rule| rule.link_calendars(&calendars))
+    }
+    pub fn balance(&self, currency: &str) -> u64 {
+        self.ledger.balance(currency)
+    }
     pub fn save(&self) -> String {
         serde_json::to_string(self)
             .expect("should be able to serialize state object")
@@ -44,15 +119,53 @@ impl State {
         serde_json::from_str(data)
             .expect("should be able to deserialize state object")
     }
-    pub fn update(&mut self, event: &Event) -> Vec<(RuleResultKind, Vec<String>)> {
-        let mut completed_rules = processing::process_simple_rules(
-            &mut self.enabled_simple_rules, &mut self.disabled_simple_rules, event
+    fn needs_date(&self) -> bool {
+        self.enabled_compound_rules.values().any(CompoundRule::needs_date)
+            || self.enabled_simple_rules.values().any(SimpleRule::needs_date)
+            || self.disabled_simple_rules.values().any(|r| r.active.is_some())
+            || self.disabled_compound_rules.values().any(|r| r.active.is_some())
+            || !self.pending_simple_rules.is_empty()
+            || !self.pending_compound_rules.is_empty()
+    }
+    // rules can't tell an unknown calendar from an empty one, so they aren't run without it
+    fn unlinked_calendar(&self) -> Option<&String> {
+        self.enabled_simple_rules
+            .values()
+            .chain(self.disabled_simple_rules.values())
+            .chain(self.pending_simple_rules.values())
+            .chain(self.locked_simple_rules.values())
+            .find_map(SimpleRule::unlinked_calendar)
+    }
+    pub fn update(&mut self, event: &Event) -> Result<Vec<ResultRecord>, ProcessingError> {
+        let event = processing::date_event(event, self.clock.as_deref());
+        let date = processing::get_event_date(&event)?;
+        // checked up front, so a failed update leaves the state untouched
+        if date.is_none() && self.needs_date() {
+            return Err(ProcessingError::MissingDate);
+        }
+        if let Some(name) = self.unlinked_calendar() {
+            return Err(ProcessingError::UnlinkedCalendar(name.clone()));
+        }
+        if let Some(date) = date {
+            processing::apply_active_windows(self, &date);
+            processing::start_periods(self, &date);
+            processing::expire_progress(self, &date);
+        }
+        let event = self.enricher.enrich(&event);
+        let completed_rules = processing::process_simple_rules(
+            &mut self.enabled_simple_rules, &mut self.disabled_simple_rules, &event
         );
         let completed_compound_rules = processing::process_compound_rules(
             &mut self.enabled_compound_rules, &mut self.disabled_compound_rules,
-            &completed_rules, event
+            &completed_rules, date
         );
-        completed_rules.extend(completed_compound_rules);
-        processing::process_rule_results(self, completed_rules)
+        let completed: Vec<_> = completed_rules.into_iter()
+            .map(|v| (v, RuleType::Simple))
+            .chain(completed_compound_rules.into_iter().map(|v| (v, RuleType::Compound)))
+            .collect();
+        for (name, _) in &completed {
+            *self.completions.entry(name.clone()).or_default() += 1;
+        }
+        Ok(processing::process_rule_results(self, completed, &event))
     }
 }
\ No newline at end of file
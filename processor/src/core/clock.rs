@@ -0,0 +1,23 @@
+use chrono::{Local, NaiveDate};
+
+// source of the current date for events sent without one
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+// always returns the same date, useful for tests and replays
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
\ No newline at end of file
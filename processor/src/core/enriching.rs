@@ -0,0 +1,79 @@
+use chrono::{Datelike, NaiveDate, Timelike};
+use common::event::{Event, EventPartValue};
+
+pub trait EventDerivation {
+    fn name(&self) -> &str;
+    fn derive(&self, event: &Event) -> Option<EventPartValue>;
+}
+
+pub struct EventEnricher {
+    derivations: Vec<Box<dyn EventDerivation>>
+}
+
+impl EventEnricher {
+    pub fn new(derivations: Vec<Box<dyn EventDerivation>>) -> Self {
+        Self { derivations }
+    }
+    pub fn add(&mut self, derivation: impl EventDerivation + 'static) {
+        self.derivations.push(Box::new(derivation));
+    }
+    pub fn enrich(&self, event: &Event) -> Event {
+        let mut enriched = event.clone();
+        for derivation in &self.derivations {
+            // parts sent by the frontend take precedence over derived ones
+            if !enriched.parts.contains_key(derivation.name()) {
+                if let Some(val) = derivation.derive(&enriched) {
+                    enriched.parts.insert(derivation.name().to_owned(), val);
+                }
+            }
+        }
+        enriched
+    }
+}
+
+impl Default for EventEnricher {
+    fn default() -> Self {
+        Self::new(vec!(
+            Box::new(CalendarField::Weekday),
+            Box::new(CalendarField::Hour),
+            Box::new(CalendarField::WeekOfYear),
+            Box::new(CalendarField::Month)
+        ))
+    }
+}
+
+pub enum CalendarField {
+    Weekday, Hour, WeekOfYear, Month,
+    // days passed since the given start date
+    DaysSinceStart(NaiveDate)
+}
+
+impl EventDerivation for CalendarField {
+    fn name(&self) -> &str {
+        match self {
+            CalendarField::Weekday => "weekday",
+            CalendarField::Hour => "hour",
+            CalendarField::WeekOfYear => "week_of_year",
+            CalendarField::Month => "month",
+            CalendarField::DaysSinceStart(_) => "days_since_start"
+        }
+    }
+    fn derive(&self, event: &Event) -> Option<EventPartValue> {
+        let on = match event.parts.get("on") {
+            Some(EventPartValue::DateBased(date)) => Some(date),
+            _ => None
+        };
+        let at = match event.parts.get("at") {
+            Some(EventPartValue::TimeBased(time)) => Some(time),
+            _ => None
+        };
+        let res = match self {
+            CalendarField::Weekday => on?.weekday().number_from_monday(),
+            CalendarField::Hour => at?.hour(),
+            CalendarField::WeekOfYear => on?.iso_week().week(),
+            CalendarField::Month => on?.month(),
+            CalendarField::DaysSinceStart(start) => u32::try_from((*on? - *start).num_days()).ok()?
+        };
+        Some(EventPartValue::NumberBased(res))
+    }
+}
@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+pub enum ProcessingError {
+    MissingDate, IncorrectDate, UnlinkedCalendar(String)
+}
+
+impl Debug for ProcessingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Display for ProcessingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessingError::MissingDate => f.write_str("event has no date and no clock is set, but some rules need one"),
+            ProcessingError::IncorrectDate => f.write_str("event date isn't a date"),
+            ProcessingError::UnlinkedCalendar(name) => write!(f, "calendar {name} is referenced but was never linked")
+        }
+    }
+}
+
+impl Error for ProcessingError {}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// balances of reward currencies, such as xp or coins, along with their history
+#[derive(Default, Serialize, Deserialize)]
+pub struct Ledger {
+    pub balances: HashMap<String, u64>,
+    pub transactions: Vec<Transaction>
+}
+
+impl Ledger {
+    pub fn balance(&self, currency: &str) -> u64 {
+        self.balances.get(currency)
+            .copied()
+            .unwrap_or_default()
+    }
+    pub(super) fn record(&mut self, transaction: Transaction) {
+        let balance = self.balances.entry(transaction.currency.clone()).or_default();
+        *balance = balance.saturating_add(transaction.amount);
+        self.transactions.push(transaction);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub currency: String,
+    pub amount: u64,
+    // rule whose result granted the amount
+    pub rule: String,
+    pub date: Option<NaiveDate>
+}
+
+impl Transaction {
+    pub fn new(currency: String, amount: u64, rule: String, date: Option<NaiveDate>) -> Self {
+        Self { currency, amount, rule, date }
+    }
+}
\ No newline at end of file
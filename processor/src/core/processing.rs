@@ -4,46 +4,259 @@ use chrono::NaiveDate;
 use common::{Advancing, AdvancingResultType};
 use common::compound_rule::CompoundRule;
 use common::event::{Event, EventPartValue};
-use common::rule_result::{RuleResult, RuleResultKind};
+use common::rule_result::{ResultRecord, RuleResult, RuleResultKind, RuleType};
 use common::simple_rule::SimpleRule;
+use crate::core::clock::Clock;
+use crate::core::error::ProcessingError;
+use crate::core::ledger::Transaction;
 use crate::core::State;
 
 pub(super) fn process_rule_results(state: &mut State,
-                                   c_rules: Vec<String>) -> Vec<(RuleResultKind, Vec<String>)> {
-    let results = advance_clean_results(&mut state.enabled_rule_results, &mut state.disabled_rule_results, c_rules);
+                                   c_rules: Vec<(String, RuleType)>,
+                                   event: &Event) -> Vec<ResultRecord> {
+    let results = advance_clean_results(
+        &mut state.enabled_rule_results, &mut state.disabled_rule_results, c_rules, &state.completions, event
+    );
     apply_special_actions(state, &results);
     results
 }
 
-fn apply_special_actions(state: &mut State, results: &Vec<(RuleResultKind, Vec<String>)>) {
-    for (kind, args) in results {
-        if let RuleResultKind::Restart = kind {
-            args.iter()
-                .for_each(|arg| {
-                    if let Some(rule) = state.disabled_simple_rules.remove_entry(arg) {
-                        state.enabled_simple_rules.insert(rule.0, rule.1);
-                    }
-                    if let Some(rule) = state.disabled_compound_rules.remove_entry(arg) {
-                        state.enabled_compound_rules.insert(rule.0, rule.1);
-                    }
-                })
+fn apply_special_actions(state: &mut State, results: &[ResultRecord]) {
+    for ResultRecord { kind, args, rule, date, .. } in results {
+        match kind {
+            RuleResultKind::Reward => record_rewards(state, args, rule, *date),
+            RuleResultKind::Restart => args.iter()
+                .for_each(|arg| restart_rule(state, arg)),
+            RuleResultKind::Unlock => args.iter()
+                .for_each(|arg| unlock_rule(state, arg)),
+            RuleResultKind::Lock => args.iter()
+                .for_each(|arg| lock_rule(state, arg)),
+            RuleResultKind::Disable => args.iter()
+                .for_each(|arg| disable_rule(state, arg)),
+            RuleResultKind::Reset => args.iter()
+                .for_each(|arg| reset_rule(state, arg)),
+            _ => ()
+        }
+    }
+}
+
+// currency and amount pairs such as `reward xp 50` are booked, other args like `reward gold_badge` stay opaque
+fn record_rewards(state: &mut State, args: &[String], rule: &str, date: Option<NaiveDate>) {
+    let mut i = 0;
+    while i < args.len() {
+        let amount = args.get(i + 1).and_then(|arg| arg.parse().ok());
+        match amount {
+            Some(amount) if args[i].parse::<u64>().is_err() => {
+                state.ledger.record(Transaction::new(args[i].clone(), amount, rule.to_owned(), date));
+                i += 2;
+            }
+            _ => i += 1
+        }
+    }
+}
+
+fn restart_rule(state: &mut State, name: &String) {
+    if let Some(rule) = state.disabled_simple_rules.remove_entry(name) {
+        state.enabled_simple_rules.insert(rule.0, rule.1);
+    }
+    if let Some(rule) = state.disabled_compound_rules.remove_entry(name) {
+        state.enabled_compound_rules.insert(rule.0, rule.1);
+    }
+    // disabled results have nothing left to give, so they start from scratch
+    if let Some((name, mut res)) = state.disabled_rule_results.remove_entry(name) {
+        res.restore();
+        state.enabled_rule_results.insert(name, res);
+    }
+}
+
+fn disable_rule(state: &mut State, name: &String) {
+    if let Some(rule) = state.enabled_simple_rules.remove_entry(name) {
+        state.disabled_simple_rules.insert(rule.0, rule.1);
+    }
+    if let Some(rule) = state.enabled_compound_rules.remove_entry(name) {
+        state.disabled_compound_rules.insert(rule.0, rule.1);
+    }
+    if let Some(res) = state.enabled_rule_results.remove_entry(name) {
+        state.disabled_rule_results.insert(res.0, res.1);
+    }
+}
+
+// progress is dropped wherever the rule currently is, its completions are kept
+fn reset_rule(state: &mut State, name: &String) {
+    if let Some(rule) = state.enabled_simple_rules.get_mut(name)
+        .or_else(|| state.disabled_simple_rules.get_mut(name)) {
+        rule.reset();
+    }
+    if let Some(rule) = state.enabled_compound_rules.get_mut(name)
+        .or_else(|| state.disabled_compound_rules.get_mut(name)) {
+        rule.reset();
+    }
+}
+
+// unlocked rules with an active window still wait for it to start
+fn unlock_rule(state: &mut State, name: &String) {
+    if let Some((name, mut rule)) = state.locked_simple_rules.remove_entry(name) {
+        rule.locked = false;
+        if rule.active.is_some() {
+            move_result(&mut state.enabled_rule_results, &mut state.pending_rule_results, &name);
+            state.pending_simple_rules.insert(name, rule);
+        } else {
+            state.enabled_simple_rules.insert(name, rule);
         }
+    } else if let Some((name, mut rule)) = state.locked_compound_rules.remove_entry(name) {
+        rule.locked = false;
+        if rule.active.is_some() {
+            move_result(&mut state.enabled_rule_results, &mut state.pending_rule_results, &name);
+            state.pending_compound_rules.insert(name, rule);
+        } else {
+            state.enabled_compound_rules.insert(name, rule);
+        }
+    }
+}
+
+// progress is kept for when the rule is unlocked again, completed and expired rules stay where they are
+fn lock_rule(state: &mut State, name: &String) {
+    // results of locked rules are kept among the enabled ones
+    move_result(&mut state.pending_rule_results, &mut state.enabled_rule_results, name);
+    if let Some((name, mut rule)) = state.enabled_simple_rules.remove_entry(name)
+        .or_else(|| state.pending_simple_rules.remove_entry(name)) {
+        rule.locked = true;
+        state.locked_simple_rules.insert(name, rule);
+    } else if let Some((name, mut rule)) = state.enabled_compound_rules.remove_entry(name)
+        .or_else(|| state.pending_compound_rules.remove_entry(name)) {
+        rule.locked = true;
+        state.locked_compound_rules.insert(name, rule);
     }
 }
 
+fn move_result(from: &mut HashMap<String, RuleResult>, to: &mut HashMap<String, RuleResult>, name: &String) {
+    if let Some((name, res)) = from.remove_entry(name) {
+        to.insert(name, res);
+    }
+}
+
+// moves rules in and out of their active windows, expired ones taking their results along
+pub(super) fn apply_active_windows(state: &mut State, date: &NaiveDate) {
+    let simple_ended = |r: &SimpleRule| r.active.as_ref().is_some_and(|w| w.ended_before(date));
+    let compound_ended = |r: &CompoundRule| r.active.as_ref().is_some_and(|w| w.ended_before(date));
+    let mut expired = Vec::new();
+    expired.extend(move_rules(&mut state.enabled_simple_rules, &mut state.expired_simple_rules, simple_ended));
+    expired.extend(move_rules(&mut state.disabled_simple_rules, &mut state.expired_simple_rules, simple_ended));
+    expired.extend(move_rules(&mut state.pending_simple_rules, &mut state.expired_simple_rules, simple_ended));
+    expired.extend(move_rules(&mut state.enabled_compound_rules, &mut state.expired_compound_rules, compound_ended));
+    expired.extend(move_rules(&mut state.disabled_compound_rules, &mut state.expired_compound_rules, compound_ended));
+    expired.extend(move_rules(&mut state.pending_compound_rules, &mut state.expired_compound_rules, compound_ended));
+    for name in expired {
+        if let Some(res) = state.enabled_rule_results.remove(&name)
+            .or_else(|| state.disabled_rule_results.remove(&name))
+            .or_else(|| state.pending_rule_results.remove(&name)) {
+            state.expired_rule_results.insert(name, res);
+        }
+    }
+    let mut started = move_rules(&mut state.pending_simple_rules, &mut state.enabled_simple_rules,
+                                 |r| r.active.as_ref().is_some_and(|w| !w.starts_after(date)));
+    started.extend(move_rules(&mut state.pending_compound_rules, &mut state.enabled_compound_rules,
+                              |r| r.active.as_ref().is_some_and(|w| !w.starts_after(date))));
+    for name in started {
+        move_result(&mut state.pending_rule_results, &mut state.enabled_rule_results, &name);
+    }
+}
+
+fn move_rules<T>(from: &mut HashMap<String, T>,
+                 to: &mut HashMap<String, T>,
+                 should_move: impl Fn(&T) -> bool) -> Vec<String> {
+    let names: Vec<_> = from
+        .iter()
+        .filter(|(_, r)| should_move(r))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in &names {
+        let rule = from.remove_entry(name).unwrap();
+        to.insert(rule.0, rule.1);
+    }
+    names
+}
+
+// rules entering a new period start over, along with their results
+pub(super) fn start_periods(state: &mut State, date: &NaiveDate) {
+    let mut restarted = start_rule_periods(
+        &mut state.enabled_simple_rules, &mut state.disabled_simple_rules, |r| r.start_period(date)
+    );
+    restarted.extend(start_rule_periods(
+        &mut state.enabled_compound_rules, &mut state.disabled_compound_rules, |r| r.start_period(date)
+    ));
+    for name in restarted {
+        if let Some(mut res) = state.disabled_rule_results.remove(&name) {
+            res.restore();
+            state.enabled_rule_results.insert(name, res);
+        } else if let Some(res) = state.enabled_rule_results.get_mut(&name) {
+            res.restore();
+        }
+    }
+}
+
+pub(super) fn expire_progress(state: &mut State, date: &NaiveDate) {
+    state.enabled_simple_rules
+        .values_mut()
+        .for_each(|r| r.expire(date));
+    state.enabled_compound_rules
+        .values_mut()
+        .for_each(|r| r.expire(date));
+}
+
+fn start_rule_periods<T>(e_rules: &mut HashMap<String, T>,
+                         d_rules: &mut HashMap<String, T>,
+                         mut start: impl FnMut(&mut T) -> bool) -> Vec<String> {
+    let mut restarted: Vec<_> = e_rules
+        .iter_mut()
+        .filter_map(|(name, r)| start(r).then(|| name.clone()))
+        .collect();
+    let reenabled: Vec<_> = d_rules
+        .iter_mut()
+        .filter_map(|(name, r)| start(r).then(|| name.clone()))
+        .collect();
+    for name in &reenabled {
+        let rule = d_rules.remove_entry(name).unwrap();
+        e_rules.insert(rule.0, rule.1);
+    }
+    restarted.extend(reenabled);
+    restarted
+}
+
 fn advance_clean_results(e_results: &mut HashMap<String, RuleResult>,
                          d_results: &mut HashMap<String, RuleResult>,
-                         c_rules: Vec<String>) -> Vec<(RuleResultKind, Vec<String>)> {
+                         c_rules: Vec<(String, RuleType)>,
+                         completions: &HashMap<String, u32>,
+                         event: &Event) -> Vec<ResultRecord> {
+    let date = get_event_date(event).ok().flatten();
+    let time = match event.parts.get("at") {
+        Some(EventPartValue::TimeBased(time)) => Some(*time),
+        _ => None
+    };
     c_rules
         .into_iter()
-        .filter_map(|name| {
+        .filter_map(|(name, rule_type)| {
             if let Some(item) = e_results.get_mut(&name) {
                 let adv_res = item.advance(&());
                 if let AdvancingResultType::Completed = adv_res.res_type {
                     let entry = e_results.remove_entry(&name).unwrap();
                     d_results.insert(entry.0, entry.1);
                 }
-                Some(adv_res.data.unwrap())
+                let count = completions.get(&name).copied().unwrap_or_default();
+                let records: Vec<_> = adv_res.data.unwrap()
+                    .into_iter()
+                    .map(|(kind, args)| ResultRecord {
+                        kind,
+                        args: args.iter()
+                            .map(|arg| fill_placeholders(arg, &name, count, event))
+                            .collect(),
+                        rule: name.clone(),
+                        rule_type: rule_type.clone(),
+                        completions: count,
+                        date, time
+                    })
+                    .collect();
+                Some(records)
             } else {
                 None
             }
@@ -52,20 +265,57 @@ fn advance_clean_results(e_results: &mut HashMap<String, RuleResult>,
         .collect()
 }
 
+// `{rule}` and `{count}` stand for the completed rule and its completions, anything else for an event part
+fn fill_placeholders(arg: &str, rule: &str, count: u32, event: &Event) -> String {
+    let mut filled = String::new();
+    let mut rest = arg;
+    while let Some((start, len)) = rest.find('{')
+        .and_then(|start| rest[start..].find('}').map(|len| (start, len))) {
+        let key = &rest[start + 1..start + len];
+        filled.push_str(&rest[..start]);
+        match key {
+            "rule" => filled.push_str(rule),
+            "count" => filled.push_str(&count.to_string()),
+            _ => match event.parts.get(key) {
+                Some(val) => filled.push_str(&val.to_string()),
+                // unknown placeholders are left as they are
+                None => filled.push_str(&rest[start..=start + len])
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
 pub(super) fn process_compound_rules(e_rules: &mut HashMap<String, CompoundRule>,
                                      d_rules: &mut HashMap<String, CompoundRule>,
-                                     c_simple_rules: &Vec<String>,
-                                     event: &Event) -> Vec<String> {
-    let adv_res = advance_compound_rules(e_rules, c_simple_rules, get_event_date(event));
+                                     c_simple_rules: &[String],
+                                     date: Option<NaiveDate>) -> Vec<String> {
+    // rules which don't need a date never look at the placeholder
+    let date = date.unwrap_or_default();
+    e_rules
+        .values_mut()
+        .for_each(|r| r.tick(&date));
+    let adv_res = advance_compound_rules(e_rules, c_simple_rules, date);
     clean_compound_rules(adv_res, e_rules, d_rules)
 }
 
-fn get_event_date(event: &Event) -> NaiveDate {
-    if let EventPartValue::DateBased(date) = event.parts.get("on")
-        .expect("date presence is guaranteed in case we're dealing with Compound/Simple rules") {
-        *date
-    } else {
-        unreachable!("date must be stored in DateBased EventPartValue")
+// events without a date are dated by the clock, if there's one
+pub(super) fn date_event(event: &Event, clock: Option<&dyn Clock>) -> Event {
+    let mut dated = event.clone();
+    if let Some(clock) = clock {
+        dated.parts.entry("on".to_owned())
+            .or_insert_with(|| EventPartValue::DateBased(clock.today()));
+    }
+    dated
+}
+
+pub(super) fn get_event_date(event: &Event) -> Result<Option<NaiveDate>, ProcessingError> {
+    match event.parts.get("on") {
+        Some(EventPartValue::DateBased(date)) => Ok(Some(*date)),
+        Some(_) => Err(ProcessingError::IncorrectDate),
+        None => Ok(None)
     }
 }
 
@@ -85,14 +335,21 @@ fn clean_compound_rules(completed: Vec<(AdvancingResultType, String)>,
 }
 
 fn advance_compound_rules(e_rules: &mut HashMap<String, CompoundRule>,
-                          c_simple_rules: &Vec<String>,
+                          c_simple_rules: &[String],
                           date: NaiveDate) -> Vec<(AdvancingResultType, String)> {
-    c_simple_rules
-        .iter()
-        .map(|v|
-            e_rules
-                .iter_mut()
-                .filter_map(|(n, r)| {
+    e_rules
+        .iter_mut()
+        .flat_map(|(n, r)| {
+            // excluded completions of the same event count as happening first, then the ones the rule
+            // doesn't await, then the awaited ones in their order, so the outcome doesn't depend on hashing
+            let (excluded, mut other): (Vec<_>, Vec<_>) = c_simple_rules
+                .iter()
+                .partition(|v| r.excludes(v));
+            other.sort_by_key(|v| (r.awaits(v), *v));
+            excluded
+                .into_iter()
+                .chain(other)
+                .filter_map(|v| {
                     let adv_res = r.advance(&(v, date));
                     match adv_res.res_type {
                         AdvancingResultType::Restarted | AdvancingResultType::Completed => Some((adv_res.res_type, n.clone())),
@@ -100,8 +357,7 @@ fn advance_compound_rules(e_rules: &mut HashMap<String, CompoundRule>,
                     }
                 })
                 .collect::<Vec<_>>()
-        )
-        .flatten()
+        })
         .collect()
 }
 
@@ -130,6 +386,7 @@ fn clean_simple_rules(completed: Vec<(AdvancingResultType, String)>,
 fn advance_simple_rules(e_rules: &mut HashMap<String, SimpleRule>, event: &Event) -> Vec<(AdvancingResultType, String)> {
     e_rules
         .iter_mut()
+        .filter(|(_, rule)| rule.handles(event))
         .filter_map(|(name, rule)| {
             let adv_res = rule.advance(event);
             match adv_res.res_type {
@@ -41,6 +41,14 @@ fn repeat() {
     assert_eq!(adv.res_type, AdvancingResultType::Restarted)
 }
 
+#[test]
+fn repeat_count() {
+    let mut parsed = parse("n: repeat 2 any arg1 arg2");
+    let first = parsed.advance(&(&default_arg_val(), default_date_val()));
+    let second = parsed.advance(&(&default_arg_val(), default_date_val()));
+    assert_eq!((first.res_type, second.res_type), (AdvancingResultType::Restarted, AdvancingResultType::Completed))
+}
+
 //every
 
 #[test]
@@ -121,6 +129,251 @@ fn seq_completed() {
     assert_eq!((adv.res_type, parsed.values), (AdvancingResultType::Completed, expected_values))
 }
 
+//atleast
+
+#[test]
+fn atleast_hit() {
+    let mut parsed = parse("n: atleast 2 arg1 arg2 arg3");
+    let adv = parsed.advance(&(&default_arg_val(), default_date_val()));
+    let expected_values = vec!("arg2".to_string(), "arg3".to_string());
+    assert_eq!((adv.res_type, parsed.values), (AdvancingResultType::Hit, expected_values))
+}
+
+#[test]
+fn atleast_distinct() {
+    let mut parsed = parse("n: atleast 2 arg1 arg2 arg3");
+    parsed.advance(&(&default_arg_val(), default_date_val()));
+    let adv = parsed.advance(&(&default_arg_val(), default_date_val()));
+    assert_eq!(adv.res_type, AdvancingResultType::None)
+}
+
+#[test]
+fn atleast_completed() {
+    let mut parsed = parse("n: atleast 2 arg1 arg2 arg3");
+    parsed.advance(&(&"arg3".to_string(), default_date_val()));
+    let adv = parsed.advance(&(&default_arg_val(), default_date_val()));
+    assert_eq!(adv.res_type, AdvancingResultType::Completed)
+}
+
+#[test]
+fn atleast_restarted() {
+    let mut parsed = parse("n: repeat atleast 1 arg1 arg2");
+    parsed.advance(&(&default_arg_val(), default_date_val()));
+    let adv = parsed.advance(&(&default_arg_val(), default_date_val()));
+    let expected_values = vec!("arg1".to_string(), "arg2".to_string());
+    assert_eq!((adv.res_type, parsed.values), (AdvancingResultType::Restarted, expected_values))
+}
+
+//within
+
+fn advance_dated(rule: &mut CompoundRule, name: &str, date: &str) -> AdvancingResultType {
+    rule.advance(&(&name.to_string(), NaiveDate::from_str(date).unwrap())).res_type
+}
+
+#[test]
+fn within_completed() {
+    let mut parsed = parse("n: all arg1 arg2 within 3d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-03");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn within_expired() {
+    let mut parsed = parse("n: all arg1 arg2 within 3d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-04");
+    let expected_values = vec!("arg1".to_string());
+    assert_eq!((adv, parsed.values), (AdvancingResultType::Hit, expected_values))
+}
+
+#[test]
+fn within_sliding() {
+    let mut parsed = parse("n: all arg1 arg2 arg3 within 3d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-02");
+    advance_dated(&mut parsed, "arg1", "2000-01-04");
+    let adv = advance_dated(&mut parsed, "arg3", "2000-01-04");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn within_seq_expired() {
+    let mut parsed = parse("n: seq arg1 arg2 arg3 within 2d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-02");
+    let adv = advance_dated(&mut parsed, "arg3", "2000-01-03");
+    let expected_values = vec!("arg1".to_string(), "arg2".to_string(), "arg3".to_string());
+    assert_eq!((adv, parsed.values), (AdvancingResultType::None, expected_values))
+}
+
+//expires
+
+#[test]
+fn expires_dropped() {
+    let mut parsed = parse("n: all arg1 arg2 expires 7d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.expire(&NaiveDate::from_str("2000-01-09").unwrap());
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-09");
+    assert_eq!((adv, parsed.values), (AdvancingResultType::Hit, vec!("arg1".to_string())))
+}
+
+#[test]
+fn expires_kept() {
+    let mut parsed = parse("n: all arg1 arg2 expires 7d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.expire(&NaiveDate::from_str("2000-01-08").unwrap());
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-08");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn expires_beyond_calendar() {
+    let mut parsed = parse("n: all arg1 arg2 expires 4000000000d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.expire(&NaiveDate::from_str("2000-01-09").unwrap());
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-09");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+//without
+
+#[test]
+fn without_completed() {
+    let mut parsed = parse("n: arg1 without arg2");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn without_blocked() {
+    let mut parsed = parse("n: arg1 without arg2");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-05");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn without_after_progress() {
+    let mut parsed = parse("n: all arg1 arg3 without arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg3", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn without_window_expired() {
+    let mut parsed = parse("n: arg1 without arg2 for 7d");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    let blocked = advance_dated(&mut parsed, "arg1", "2000-01-07");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-08");
+    assert_eq!((blocked, adv), (AdvancingResultType::None, AdvancingResultType::Completed))
+}
+
+#[test]
+fn without_window_first_event() {
+    let mut parsed = parse("n: arg1 without arg2 for 7d");
+    let blocked = advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-08");
+    assert_eq!((blocked, adv), (AdvancingResultType::None, AdvancingResultType::Completed))
+}
+
+#[test]
+fn without_window_after_reset() {
+    let mut parsed = parse("n: repeat arg1 without arg2 for 7d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.reset();
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-08");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn without_restarted() {
+    let mut parsed = parse("n: repeat any arg1 arg3 without arg2");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.reset();
+    let adv = advance_dated(&mut parsed, "arg3", "2000-01-02");
+    assert_eq!(adv, AdvancingResultType::Restarted)
+}
+
+#[test]
+fn without_window_outlives_restart() {
+    let mut parsed = parse("n: repeat arg1 without arg2 for 7d");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    parsed.reset();
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-02");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+//strictseq
+
+#[test]
+fn strictseq_completed() {
+    let mut parsed = parse("n: strictseq arg1 arg2 arg1");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn strictseq_broken() {
+    let mut parsed = parse("n: strictseq arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "other", "2000-01-01");
+    let expected_values = vec!("arg1".to_string(), "arg2".to_string());
+    assert_eq!((adv, parsed.values), (AdvancingResultType::None, expected_values))
+}
+
+#[test]
+fn strictseq_restarted_by_first() {
+    let mut parsed = parse("n: strictseq arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let expected_values = vec!("arg2".to_string());
+    assert_eq!((adv, parsed.values), (AdvancingResultType::Hit, expected_values))
+}
+
+#[test]
+fn seq_ignores_other() {
+    let mut parsed = parse("n: seq arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "other", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn gap_events_exceeded() {
+    let mut parsed = parse("n: strictseq arg1 arg2 gap 1");
+    let date = default_date_val();
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.tick(&date);
+    parsed.tick(&date);
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn gap_events_kept() {
+    let mut parsed = parse("n: strictseq arg1 arg2 gap 1");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.tick(&default_date_val());
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn gap_days_exceeded() {
+    let mut parsed = parse("n: seq arg1 arg2 gap 1d");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    parsed.tick(&NaiveDate::from_str("2000-01-03").unwrap());
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-03");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
 //streak
 
 #[test]
@@ -155,6 +408,52 @@ fn streak_two_completed() {
                (AdvancingResultType::Completed, expected_values, vec!(NaiveDate::from_str("2000-01-02").unwrap())))
 }
 
+//daily streak
+
+#[test]
+fn daily_streak_partial_day() {
+    let mut parsed = parse("n: streak 2 all arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-02");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn daily_streak_completed() {
+    let mut parsed = parse("n: streak 2 all arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-02");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-02");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn daily_streak_repeated_completion() {
+    let mut parsed = parse("n: streak 1 all arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-01");
+    assert_eq!(adv, AdvancingResultType::None)
+}
+
+#[test]
+fn daily_streak_atleast() {
+    let mut parsed = parse("n: streak 2 atleast 1 arg1 arg2");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    let adv = advance_dated(&mut parsed, "arg1", "2000-01-02");
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
+#[test]
+fn daily_streak_broken() {
+    let mut parsed = parse("n: streak 2 all arg1 arg2");
+    advance_dated(&mut parsed, "arg1", "2000-01-01");
+    advance_dated(&mut parsed, "arg2", "2000-01-01");
+    advance_dated(&mut parsed, "arg1", "2000-01-03");
+    let adv = advance_dated(&mut parsed, "arg2", "2000-01-03");
+    assert_eq!(adv, AdvancingResultType::Hit)
+}
+
 //rule
 
 #[test]
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveTime};
+use common::event::{Event, EventPartValue};
+use processor::core::enriching::{CalendarField, EventDerivation, EventEnricher};
+
+fn default_event() -> Event {
+    let parts = [
+        ("player", EventPartValue::NumberBased(1)),
+        ("on", EventPartValue::DateBased(NaiveDate::from_str("2000-01-01").unwrap())),
+        ("at", EventPartValue::TimeBased(NaiveTime::from_str("22:30").unwrap()))
+    ].into_iter()
+        .map(|(kw, val)| (kw.to_string(), val))
+        .collect();
+    Event::new(parts)
+}
+
+fn number_part(event: &Event, kw: &str) -> Option<u32> {
+    if let Some(EventPartValue::NumberBased(val)) = event.parts.get(kw) {
+        Some(*val)
+    } else {
+        None
+    }
+}
+
+struct Doubled;
+
+impl EventDerivation for Doubled {
+    fn name(&self) -> &str {
+        "doubled"
+    }
+    fn derive(&self, event: &Event) -> Option<EventPartValue> {
+        number_part(event, "player").map(|v| EventPartValue::NumberBased(v * 2))
+    }
+}
+
+#[test]
+fn default_fields() {
+    let enriched = EventEnricher::default().enrich(&default_event());
+    let fields: Vec<_> = ["weekday", "hour", "week_of_year", "month"]
+        .iter()
+        .map(|kw| number_part(&enriched, kw))
+        .collect();
+    assert_eq!(fields, vec!(Some(6), Some(22), Some(52), Some(1)))
+}
+
+#[test]
+fn no_date_fields() {
+    let mut event = default_event();
+    event.parts.remove("on");
+    let enriched = EventEnricher::default().enrich(&event);
+    assert_eq!((number_part(&enriched, "weekday"), number_part(&enriched, "hour")), (None, Some(22)))
+}
+
+#[test]
+fn days_since_start() {
+    let mut enricher = EventEnricher::new(vec!());
+    enricher.add(CalendarField::DaysSinceStart(NaiveDate::from_str("1999-12-25").unwrap()));
+    let enriched = enricher.enrich(&default_event());
+    assert_eq!(number_part(&enriched, "days_since_start"), Some(7))
+}
+
+#[test]
+fn days_before_start() {
+    let mut enricher = EventEnricher::new(vec!());
+    enricher.add(CalendarField::DaysSinceStart(NaiveDate::from_str("2000-01-02").unwrap()));
+    let enriched = enricher.enrich(&default_event());
+    assert_eq!(number_part(&enriched, "days_since_start"), None)
+}
+
+#[test]
+fn event_part_not_overwritten() {
+    let mut event = default_event();
+    event.parts.insert("hour".to_string(), EventPartValue::NumberBased(3));
+    let enriched = EventEnricher::default().enrich(&event);
+    assert_eq!(number_part(&enriched, "hour"), Some(3))
+}
+
+#[test]
+fn custom_derivation() {
+    let mut enricher = EventEnricher::new(vec!());
+    enricher.add(Doubled);
+    let enriched = enricher.enrich(&default_event());
+    assert_eq!(number_part(&enriched, "doubled"), Some(2))
+}
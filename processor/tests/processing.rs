@@ -2,7 +2,10 @@ use std::str::FromStr;
 
 use chrono::{NaiveDate, NaiveTime};
 use common::event::{Event, EventPartValue};
-use common::rule_result::RuleResultKind;
+use common::rule_result::{ResultRecord, RuleResultKind, RuleType};
+use processor::core::clock::FixedClock;
+use processor::core::error::ProcessingError;
+use processor::core::ledger::Transaction;
 use processor::core::State;
 
 fn default_event() -> Event {
@@ -22,6 +25,12 @@ fn default_event() -> Event {
     Event::new(parts)
 }
 
+fn plain(res: Vec<ResultRecord>) -> Vec<(RuleResultKind, Vec<String>)> {
+    res.into_iter()
+        .map(|r| (r.kind, r.args))
+        .collect()
+}
+
 fn dump_simple_data(state: &State) -> (usize, usize) {
     (state.enabled_simple_rules.len(), state.disabled_simple_rules.len())
 }
@@ -44,7 +53,7 @@ fn single_simple_hit() {
         vec!()
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -60,7 +69,7 @@ fn single_simple_completed() {
         vec!()
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -80,7 +89,7 @@ fn multiple_simple_completed() {
         vec!()
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -100,7 +109,7 @@ fn multiple_simple_mixed() {
         vec!()
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -108,8 +117,184 @@ fn multiple_simple_mixed() {
     )
 }
 
+#[test]
+fn simple_derived_part() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("n: hour >=10 weekday 6").unwrap(),
+            parser::parse_simple_rule("n2: hour <10").unwrap(),
+            parser::parse_simple_rule("n3: strict days_since_start 0").unwrap()
+        ),
+        vec!(),
+        vec!()
+    );
+    state.update(&default_event()).unwrap();
+    assert_eq!(dump_simple_data(&state), (2, 1))
+}
+
+#[test]
+fn simple_category() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("n: when submission: player 1").unwrap(),
+            parser::parse_simple_rule("n2: when login: player 1").unwrap(),
+            parser::parse_simple_rule("n3: player 1").unwrap()
+        ),
+        vec!(),
+        vec!()
+    );
+    let event = default_event();
+    state.update(&Event::categorized("submission".to_string(), event.parts)).unwrap();
+    assert_eq!(
+        (state.enabled_simple_rules.contains_key("n2"), dump_simple_data(&state)),
+        (true, (1, 2))
+    )
+}
+
+#[test]
+fn simple_uncategorized_event() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("n: when submission: player 1").unwrap(),
+            parser::parse_simple_rule("n2: player 1").unwrap()
+        ),
+        vec!(),
+        vec!()
+    );
+    state.update(&default_event()).unwrap();
+    assert_eq!(dump_simple_data(&state), (1, 1))
+}
+
+fn dated_event(date: &str) -> Event {
+    let mut event = default_event();
+    event.parts.insert("on".to_string(), EventPartValue::DateBased(NaiveDate::from_str(date).unwrap()));
+    event
+}
+
+fn calendar_state(rules: &[&str], calendars: &[&str]) -> Result<State, String> {
+    let mut state = State::new(
+        rules.iter()
+            .map(|r| parser::parse_simple_rule(r).unwrap())
+            .collect(),
+        vec!(),
+        vec!()
+    );
+    state.link_calendars(calendars.iter()
+        .map(|c| parser::parse_calendar(c).unwrap())
+        .collect())?;
+    Ok(state)
+}
+
+#[test]
+fn simple_calendar_in() {
+    let mut state = calendar_state(
+        &["n: on in(exams)", "n2: on not_in(exams)"],
+        &["calendar exams = 1999.12.20..2000.01.10"]
+    ).unwrap();
+    state.update(&default_event()).unwrap();
+    assert_eq!(
+        (state.enabled_simple_rules.contains_key("n2"), dump_simple_data(&state)),
+        (true, (1, 1))
+    )
+}
+
+#[test]
+fn simple_calendar_not_in() {
+    let mut state = calendar_state(
+        &["n: on in(exams)", "n2: on not_in(exams)"],
+        &["calendar exams = 2000.06.10..2000.06.30"]
+    ).unwrap();
+    state.update(&default_event()).unwrap();
+    assert_eq!(
+        (state.enabled_simple_rules.contains_key("n"), dump_simple_data(&state)),
+        (true, (1, 1))
+    )
+}
+
+#[test]
+fn simple_calendar_unknown() {
+    let state = calendar_state(&["n: on in(exams)"], &["calendar holidays = *.12.25"]);
+    assert_eq!(state.err(), Some("exams".to_string()))
+}
+
+#[test]
+fn simple_calendar_unlinked() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("n: on in(exams)").unwrap(),
+            parser::parse_simple_rule("n2: on not_in(exams)").unwrap()
+        ),
+        vec!(),
+        vec!()
+    );
+    let res = state.update(&default_event());
+    assert_eq!(
+        (res.err().map(|e| e.to_string()), dump_simple_data(&state)),
+        (Some("calendar exams is referenced but was never linked".to_string()), (2, 0))
+    )
+}
+
+#[test]
+fn simple_skipping_streak() {
+    let mut state = calendar_state(
+        &["n: on streak(3) skipping holidays", "n2: on streak(3)"],
+        &["calendar holidays = 2000.01.05"]
+    ).unwrap();
+    for date in ["2000-01-03", "2000-01-04", "2000-01-06"] {
+        state.update(&dated_event(date)).unwrap();
+    }
+    assert_eq!(
+        (state.enabled_simple_rules.contains_key("n2"), dump_simple_data(&state)),
+        (true, (1, 1))
+    )
+}
+
+#[test]
+fn simple_skipping_streak_activity_on_skipped_day() {
+    let mut state = calendar_state(
+        &["n: on streak(3) skipping holidays"],
+        &["calendar holidays = 2000.01.05, 2000.01.06"]
+    ).unwrap();
+    for date in ["2000-01-04", "2000-01-05", "2000-01-07", "2000-01-08"] {
+        state.update(&dated_event(date)).unwrap();
+    }
+    assert_eq!(dump_simple_data(&state), (0, 1))
+}
+
 //compound
 
+#[test]
+fn compound_without_same_event() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("solved: player 1").unwrap(),
+            parser::parse_simple_rule("failed: did 1").unwrap()
+        ),
+        vec!(parser::parse_compound_rule("first_try: solved without failed").unwrap()),
+        vec!()
+    );
+    state.update(&default_event()).unwrap();
+    assert_eq!(dump_compound_data(&state), (1, 0))
+}
+
+#[test]
+fn compound_strictseq_same_event() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("first: player 1").unwrap(),
+            parser::parse_simple_rule("second: did 1").unwrap(),
+            parser::parse_simple_rule("other: with 1").unwrap()
+        ),
+        vec!(
+            parser::parse_compound_rule("forward: strictseq first second").unwrap(),
+            parser::parse_compound_rule("backward: strictseq second first").unwrap()
+        ),
+        vec!()
+    );
+    state.update(&default_event()).unwrap();
+    assert_eq!(dump_compound_data(&state), (0, 2))
+}
+
 #[test]
 fn single_compound_hit() {
     let mut state = State::new(
@@ -118,7 +303,7 @@ fn single_compound_hit() {
         vec!()
     );
     let data_after_init = dump_compound_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_compound_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -134,7 +319,7 @@ fn single_compound_completed() {
         vec!()
     );
     let data_after_init = dump_compound_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_compound_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -158,7 +343,7 @@ fn multiple_compound_completed() {
         vec!()
     );
     let data_after_init = dump_compound_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_compound_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -182,7 +367,7 @@ fn multiple_compound_mixed() {
         vec!()
     );
     let data_after_init = dump_compound_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_compound_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -190,6 +375,59 @@ fn multiple_compound_mixed() {
     )
 }
 
+fn undated_event() -> Event {
+    let mut event = default_event();
+    event.parts.remove("on");
+    event
+}
+
+#[test]
+fn compound_undated_non_streak() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("simple_rule_1: player 1").unwrap()),
+        vec!(parser::parse_compound_rule("compound_rule_1: all simple_rule_1").unwrap()),
+        vec!()
+    );
+    state.update(&undated_event()).unwrap();
+    assert_eq!(dump_compound_data(&state), (0, 1))
+}
+
+#[test]
+fn compound_undated_streak() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("simple_rule_1: player 1").unwrap()),
+        vec!(parser::parse_compound_rule("compound_rule_1: streak 2 simple_rule_1").unwrap()),
+        vec!()
+    );
+    let res = state.update(&undated_event());
+    assert_eq!(
+        (matches!(res, Err(ProcessingError::MissingDate)), dump_simple_data(&state)),
+        (true, (1, 0))
+    )
+}
+
+#[test]
+fn compound_clock_fallback() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("simple_rule_1: repeat 2 player 1").unwrap()),
+        vec!(parser::parse_compound_rule("compound_rule_1: streak 2 simple_rule_1").unwrap()),
+        vec!()
+    );
+    for date in ["2000-01-01", "2000-01-02"] {
+        state.clock = Some(Box::new(FixedClock(NaiveDate::from_str(date).unwrap())));
+        state.update(&undated_event()).unwrap();
+    }
+    assert_eq!(dump_compound_data(&state), (0, 1))
+}
+
+#[test]
+fn incorrect_event_date() {
+    let mut state = State::new(vec!(), vec!(), vec!());
+    let mut event = default_event();
+    event.parts.insert("on".to_string(), EventPartValue::NumberBased(1));
+    assert!(matches!(state.update(&event), Err(ProcessingError::IncorrectDate)))
+}
+
 //result
 
 #[test]
@@ -200,7 +438,7 @@ fn single_res_hit() {
         vec!(parser::parse_rule_result("compound_rule_1 -> repeat msg Hi").unwrap())
     );
     let data_after_init = dump_res_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_res_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -216,7 +454,7 @@ fn single_res_completed() {
         vec!(parser::parse_rule_result("compound_rule_1 -> msg Hi").unwrap())
     );
     let data_after_init = dump_res_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_res_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -244,7 +482,7 @@ fn multiple_res_completed() {
         )
     );
     let data_after_init = dump_res_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_res_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -273,7 +511,7 @@ fn multiple_res_mixed() {
         )
     );
     let data_after_init = dump_res_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_res_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -297,7 +535,7 @@ fn no_restart_res() {
         )
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -319,7 +557,7 @@ fn restart_res() {
         )
     );
     let data_after_init = dump_simple_data(&state);
-    state.update(&default_event());
+    state.update(&default_event()).unwrap();
     let data_after_update = dump_simple_data(&state);
     assert_eq!(
         (data_after_init, data_after_update),
@@ -327,6 +565,69 @@ fn restart_res() {
     )
 }
 
+#[test]
+fn restart_res_of_result() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("a: repeat + player 1").unwrap(),
+            parser::parse_simple_rule("b: repeat + did 1").unwrap()
+        ),
+        vec!(),
+        vec!(
+            parser::parse_rule_result("a -> msg Hi").unwrap(),
+            parser::parse_rule_result("b -> restart a").unwrap()
+        )
+    );
+    let first_res = plain(state.update(&default_event()).unwrap());
+    let second_res = plain(state.update(&default_event()).unwrap());
+    let hi = (RuleResultKind::Message, vec!("Hi".to_string()));
+    assert_eq!(
+        (first_res.contains(&hi), second_res.contains(&hi)),
+        (true, true)
+    )
+}
+
+#[test]
+fn disable_res() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("path_a: player 1").unwrap(),
+            parser::parse_simple_rule("path_b: did 2").unwrap()
+        ),
+        vec!(),
+        vec!(
+            parser::parse_rule_result("path_a -> disable path_b").unwrap(),
+            parser::parse_rule_result("path_b -> msg B").unwrap()
+        )
+    );
+    state.update(&default_event()).unwrap();
+    assert_eq!(
+        (state.disabled_simple_rules.contains_key("path_b"), dump_simple_data(&state), dump_res_data(&state)),
+        (true, (0, 2), (0, 2))
+    )
+}
+
+#[test]
+fn reset_res() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("progress: repeat + player 1").unwrap(),
+            parser::parse_simple_rule("fail: repeat + did 2").unwrap()
+        ),
+        vec!(parser::parse_compound_rule("combo: all progress other").unwrap()),
+        vec!(parser::parse_rule_result("fail -> repeat reset combo").unwrap())
+    );
+    state.update(&default_event()).unwrap();
+    let mut event = default_event();
+    event.parts.insert("did".to_string(), EventPartValue::NumberBased(2));
+    event.parts.insert("player".to_string(), EventPartValue::NumberBased(2));
+    state.update(&event).unwrap();
+    assert_eq!(
+        state.enabled_compound_rules.get("combo").unwrap().values,
+        vec!("progress".to_string(), "other".to_string())
+    )
+}
+
 #[test]
 fn res_restarting() {
     let mut state = State::new(
@@ -340,9 +641,9 @@ fn res_restarting() {
             parser::parse_rule_result("compound_rule -> repeat msg Thanks").unwrap()
         )
     );
-    let first_res = state.update(&default_event());
-    let second_res = state.update(&default_event());
-    let third_res = state.update(&default_event());
+    let first_res = plain(state.update(&default_event()).unwrap());
+    let second_res = plain(state.update(&default_event()).unwrap());
+    let third_res = plain(state.update(&default_event()).unwrap());
     assert_eq!(
         (first_res, second_res, third_res),
         (
@@ -366,9 +667,9 @@ fn res_non_restarting() {
             parser::parse_rule_result("compound_rule -> repeat msg Thanks").unwrap()
         )
     );
-    let first_res = state.update(&default_event());
-    let second_res = state.update(&default_event());
-    let third_res = state.update(&default_event());
+    let first_res = plain(state.update(&default_event()).unwrap());
+    let second_res = plain(state.update(&default_event()).unwrap());
+    let third_res = plain(state.update(&default_event()).unwrap());
     assert_eq!(
         (first_res, second_res, third_res),
         (
@@ -377,4 +678,418 @@ fn res_non_restarting() {
             vec!()
         )
     )
+}
+
+#[test]
+fn res_limited_repeats() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("simple_rule: repeat + player 1 did 1 with all(1)").unwrap()
+        ),
+        vec!(
+            parser::parse_compound_rule("compound_rule: repeat 3 any simple_rule").unwrap()
+        ),
+        vec!(
+            parser::parse_rule_result("compound_rule -> repeat 2 msg Thanks").unwrap()
+        )
+    );
+    let results: Vec<_> = (0..3)
+        .map(|_| state.update(&default_event()).unwrap().len())
+        .collect();
+    state.update(&default_event()).unwrap();
+    assert_eq!(
+        (results, dump_compound_data(&state), dump_res_data(&state)),
+        (vec!(1, 1, 0), (0, 1), (0, 1))
+    )
+}
+
+//periods
+
+#[test]
+fn simple_resets_daily() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: player 1 resets daily").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("n -> msg Done").unwrap())
+    );
+    let results: Vec<_> = ["2000-01-01", "2000-01-01", "2000-01-02"]
+        .into_iter()
+        .map(|date| state.update(&dated_event(date)).unwrap().len())
+        .collect();
+    assert_eq!(
+        (results, dump_simple_data(&state), dump_res_data(&state)),
+        (vec!(1, 0, 1), (0, 1), (0, 1))
+    )
+}
+
+#[test]
+fn simple_resets_weekly() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: player 1 resets weekly(MONDAY)").unwrap()),
+        vec!(),
+        vec!()
+    );
+    // 2000-01-02 is a Sunday, 2000-01-03 a Monday
+    state.update(&dated_event("2000-01-01")).unwrap();
+    state.update(&dated_event("2000-01-02")).unwrap();
+    let same_week = dump_simple_data(&state);
+    state.update(&dated_event("2000-01-03")).unwrap();
+    assert_eq!((same_week, dump_simple_data(&state)), ((0, 1), (0, 1)))
+}
+
+#[test]
+fn compound_resets_partial_progress() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("a: repeat + player 1").unwrap(),
+            parser::parse_simple_rule("b: repeat + did 2").unwrap()
+        ),
+        vec!(parser::parse_compound_rule("n: all a b resets monthly").unwrap()),
+        vec!()
+    );
+    state.update(&dated_event("2000-01-31")).unwrap();
+    let mut event = dated_event("2000-02-01");
+    event.parts.insert("did".to_string(), EventPartValue::NumberBased(2));
+    event.parts.insert("player".to_string(), EventPartValue::NumberBased(2));
+    state.update(&event).unwrap();
+    assert_eq!(dump_compound_data(&state), (1, 0))
+}
+
+#[test]
+fn resets_undated() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: player 1 resets daily").unwrap()),
+        vec!(),
+        vec!()
+    );
+    assert!(matches!(state.update(&undated_event()), Err(ProcessingError::MissingDate)))
+}
+
+#[test]
+fn simple_expires() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: did all(1,2) expires 7d").unwrap()),
+        vec!(),
+        vec!()
+    );
+    state.update(&dated_event("2000-01-01")).unwrap();
+    let mut event = dated_event("2000-01-10");
+    event.parts.insert("did".to_string(), EventPartValue::NumberBased(2));
+    state.update(&event).unwrap();
+    let after_expiry = dump_simple_data(&state);
+    state.update(&dated_event("2000-01-11")).unwrap();
+    assert_eq!((after_expiry, dump_simple_data(&state)), ((1, 0), (0, 1)))
+}
+
+#[test]
+fn simple_expires_beyond_calendar() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: did all(1,2) expires 4000000000d").unwrap()),
+        vec!(),
+        vec!()
+    );
+    state.update(&dated_event("2000-01-01")).unwrap();
+    let mut event = dated_event("2000-01-10");
+    event.parts.insert("did".to_string(), EventPartValue::NumberBased(2));
+    state.update(&event).unwrap();
+    assert_eq!(dump_simple_data(&state), (0, 1))
+}
+
+//active windows
+
+fn seasonal_state() -> State {
+    State::new(
+        vec!(parser::parse_simple_rule("n: repeat + player 1 active 2000.01.10..2000.01.20").unwrap()),
+        vec!(parser::parse_compound_rule("c: repeat any n active 2000.01.10..2000.01.20").unwrap()),
+        vec!(parser::parse_rule_result("c -> repeat msg Hi").unwrap())
+    )
+}
+
+#[test]
+fn active_pending() {
+    let mut state = seasonal_state();
+    let res = plain(state.update(&dated_event("2000-01-09")).unwrap());
+    assert_eq!(
+        (res.len(), state.pending_simple_rules.len(), state.pending_compound_rules.len(), dump_simple_data(&state)),
+        (0, 1, 1, (0, 0))
+    )
+}
+
+#[test]
+fn active_pending_results() {
+    let mut state = seasonal_state();
+    state.update(&dated_event("2000-01-09")).unwrap();
+    let before = (state.pending_rule_results.len(), state.enabled_rule_results.len());
+    state.update(&dated_event("2000-01-10")).unwrap();
+    assert_eq!(
+        (before, state.pending_rule_results.len(), state.enabled_rule_results.len()),
+        ((1, 0), 0, 1)
+    )
+}
+
+#[test]
+fn active_expired_while_pending() {
+    let mut state = seasonal_state();
+    state.update(&dated_event("2000-01-21")).unwrap();
+    assert_eq!(
+        (state.pending_rule_results.len(), state.expired_rule_results.len()),
+        (0, 1)
+    )
+}
+
+#[test]
+fn active_inside() {
+    let mut state = seasonal_state();
+    state.update(&dated_event("2000-01-09")).unwrap();
+    let res = plain(state.update(&dated_event("2000-01-10")).unwrap());
+    assert_eq!(
+        (res.len(), state.pending_simple_rules.len(), dump_simple_data(&state), dump_compound_data(&state)),
+        (1, 0, (1, 0), (1, 0))
+    )
+}
+
+#[test]
+fn active_expired() {
+    let mut state = seasonal_state();
+    state.update(&dated_event("2000-01-15")).unwrap();
+    let res = plain(state.update(&dated_event("2000-01-21")).unwrap());
+    assert_eq!(
+        (res.len(), state.expired_simple_rules.len(), state.expired_compound_rules.len(),
+         state.expired_rule_results.len(), dump_res_data(&state)),
+        (0, 1, 1, 1, (0, 0))
+    )
+}
+
+//locked
+
+#[test]
+fn unlock_pending_results() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("n: player 1").unwrap(),
+            parser::parse_simple_rule("seasonal: locked did 1 active 2000.01.10..2000.01.20").unwrap()
+        ),
+        vec!(),
+        vec!(
+            parser::parse_rule_result("n -> unlock seasonal").unwrap(),
+            parser::parse_rule_result("seasonal -> msg Hi").unwrap()
+        )
+    );
+    state.update(&dated_event("2000-01-01")).unwrap();
+    assert_eq!(
+        (state.pending_simple_rules.len(), state.pending_rule_results.contains_key("seasonal")),
+        (1, true)
+    )
+}
+
+#[test]
+fn locked_rules_unavailable() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("n: locked player 1").unwrap()),
+        vec!(parser::parse_compound_rule("c: locked any n").unwrap()),
+        vec!(parser::parse_rule_result("n -> msg Hi").unwrap())
+    );
+    let res = plain(state.update(&default_event()).unwrap());
+    assert_eq!(
+        (res.len(), state.locked_simple_rules.len(), state.locked_compound_rules.len(),
+         dump_simple_data(&state), dump_compound_data(&state)),
+        (0, 1, 1, (0, 0), (0, 0))
+    )
+}
+
+#[test]
+fn unlock_res() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("chapter_1: player 1").unwrap(),
+            parser::parse_simple_rule("chapter_2: locked did 1").unwrap()
+        ),
+        vec!(),
+        vec!(
+            parser::parse_rule_result("chapter_1 -> unlock chapter_2").unwrap(),
+            parser::parse_rule_result("chapter_2 -> msg Done").unwrap()
+        )
+    );
+    let first_res = plain(state.update(&default_event()).unwrap());
+    let second_res = plain(state.update(&default_event()).unwrap());
+    assert_eq!(
+        (first_res, second_res, state.locked_simple_rules.len(), dump_simple_data(&state)),
+        (
+            vec!((RuleResultKind::Unlock, vec!("chapter_2".to_string()))),
+            vec!((RuleResultKind::Message, vec!("Done".to_string()))),
+            0, (0, 2)
+        )
+    )
+}
+
+#[test]
+fn lock_res() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("opening: player 1").unwrap(),
+            parser::parse_simple_rule("bonus: repeat + did 1").unwrap()
+        ),
+        vec!(parser::parse_compound_rule("combo: repeat any bonus").unwrap()),
+        vec!(
+            parser::parse_rule_result("opening -> lock all bonus combo").unwrap(),
+            parser::parse_rule_result("bonus -> repeat msg Bonus").unwrap()
+        )
+    );
+    state.update(&default_event()).unwrap();
+    let res = plain(state.update(&default_event()).unwrap());
+    assert_eq!(
+        (res.len(), state.locked_simple_rules.get("bonus").map(|r| r.locked),
+         state.locked_compound_rules.get("combo").map(|r| r.locked)),
+        (0, Some(true), Some(true))
+    )
+}
+
+#[test]
+fn lock_then_unlock_res() {
+    let mut state = State::new(
+        vec!(
+            parser::parse_simple_rule("opening: player 1").unwrap(),
+            parser::parse_simple_rule("closing: on any(2000.01.02)").unwrap(),
+            parser::parse_simple_rule("bonus: repeat + did 1").unwrap()
+        ),
+        vec!(),
+        vec!(
+            parser::parse_rule_result("opening -> lock bonus").unwrap(),
+            parser::parse_rule_result("closing -> unlock bonus").unwrap()
+        )
+    );
+    state.update(&dated_event("2000-01-01")).unwrap();
+    state.update(&dated_event("2000-01-02")).unwrap();
+    assert_eq!(
+        (state.locked_simple_rules.len(), state.enabled_simple_rules.get("bonus").map(|r| r.locked)),
+        (0, Some(false))
+    )
+}
+
+//templates
+
+#[test]
+fn res_template() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("scored: repeat + player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("scored -> repeat msg \"You scored {achieving} on exercise {of}!\"").unwrap())
+    );
+    let res = plain(state.update(&default_event()).unwrap());
+    assert_eq!(res, vec!((RuleResultKind::Message, vec!("You scored 1 on exercise 1!".to_string()))))
+}
+
+#[test]
+fn res_template_rule_and_count() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("scored: repeat + player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("scored -> repeat msg \"{rule} done {count} times, {unknown}\"").unwrap())
+    );
+    state.update(&default_event()).unwrap();
+    let res = plain(state.update(&default_event()).unwrap());
+    assert_eq!(res, vec!((RuleResultKind::Message, vec!("scored done 2 times, {unknown}".to_string()))))
+}
+
+//records
+
+#[test]
+fn res_record() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("simple_rule: repeat + player 1").unwrap()),
+        vec!(parser::parse_compound_rule("streak_7: repeat any simple_rule").unwrap()),
+        vec!(parser::parse_rule_result("streak_7 -> repeat msg Badge").unwrap())
+    );
+    state.update(&default_event()).unwrap();
+    let res = state.update(&default_event()).unwrap();
+    let expected = ResultRecord {
+        kind: RuleResultKind::Message,
+        args: vec!("Badge".to_string()),
+        rule: "streak_7".to_string(),
+        rule_type: RuleType::Compound,
+        completions: 2,
+        date: Some(NaiveDate::from_str("2000-01-01").unwrap()),
+        time: Some(NaiveTime::from_str("10:00").unwrap())
+    };
+    assert_eq!(res, vec!(expected))
+}
+
+#[test]
+fn res_record_undated() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("simple_rule: player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("simple_rule -> msg Hi").unwrap())
+    );
+    let res = state.update(&undated_event()).unwrap();
+    let record = res.first().unwrap();
+    assert_eq!(
+        (record.rule_type.clone(), record.completions, record.date, record.time.is_some()),
+        (RuleType::Simple, 1, None, true)
+    )
+}
+
+//rewards
+
+#[test]
+fn typed_rewards() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("solved: repeat + player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("solved -> repeat reward xp 50 coins 10").unwrap())
+    );
+    state.update(&default_event()).unwrap();
+    state.update(&default_event()).unwrap();
+    let expected_last = Transaction::new(
+        "coins".to_string(), 10, "solved".to_string(), Some(NaiveDate::from_str("2000-01-01").unwrap())
+    );
+    assert_eq!(
+        (state.balance("xp"), state.balance("coins"), state.ledger.transactions.len(), state.ledger.transactions.last()),
+        (100, 20, 4, Some(&expected_last))
+    )
+}
+
+#[test]
+fn opaque_rewards() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("solved: player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("solved -> reward all gold_badge bonus").unwrap())
+    );
+    let res = plain(state.update(&default_event()).unwrap());
+    let expected_args = vec!("gold_badge".to_string(), "bonus".to_string());
+    assert_eq!(
+        (res, state.ledger.balances.is_empty(), state.ledger.transactions.is_empty()),
+        (vec!((RuleResultKind::Reward, expected_args)), true, true)
+    )
+}
+
+#[test]
+fn mixed_rewards() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("solved: player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("solved -> reward gold_badge xp 50 level").unwrap())
+    );
+    let res = plain(state.update(&default_event()).unwrap());
+    let expected_args = vec!(
+        "gold_badge".to_string(), "xp".to_string(), "50".to_string(), "level".to_string()
+    );
+    assert_eq!(
+        (res, state.balance("xp"), state.balance("level"), state.ledger.transactions.len()),
+        (vec!((RuleResultKind::Reward, expected_args)), 50, 0, 1)
+    )
+}
+
+#[test]
+fn rewards_after_reload() {
+    let mut state = State::new(
+        vec!(parser::parse_simple_rule("solved: repeat + player 1").unwrap()),
+        vec!(),
+        vec!(parser::parse_rule_result("solved -> repeat reward xp 50").unwrap())
+    );
+    state.update(&default_event()).unwrap();
+    let mut state = State::load(&state.save());
+    state.update(&default_event()).unwrap();
+    assert_eq!(state.balance("xp"), 100)
 }
\ No newline at end of file
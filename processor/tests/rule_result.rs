@@ -27,6 +27,45 @@ fn repeat() {
     assert_eq!(adv.res_type, AdvancingResultType::Hit)
 }
 
+#[test]
+fn repeat_count() {
+    let mut parsed = parse("n -> repeat 2 msg Hi");
+    let first = parsed.advance(&());
+    let second = parsed.advance(&());
+    assert_eq!(
+        (first.res_type, second.res_type, second.data),
+        (AdvancingResultType::Hit, AdvancingResultType::Completed, single_data(RuleResultKind::Message, vec!("Hi".to_string())))
+    )
+}
+
+#[test]
+fn repeat_count_mixed() {
+    let mut parsed = parse("n -> repeat 2 msg a; repeat msg c");
+    parsed.advance(&());
+    parsed.advance(&());
+    let third = parsed.advance(&());
+    assert_eq!(
+        (third.res_type, third.data),
+        (AdvancingResultType::Hit, single_data(RuleResultKind::Message, vec!("c".to_string())))
+    )
+}
+
+#[test]
+fn repeat_count_mixed_choice() {
+    let mut parsed = parse("n -> repeat 2 msg choice a b; repeat msg c");
+    parsed.advance(&());
+    let second = parsed.advance(&());
+    let third = parsed.advance(&());
+    let expected_second = Some(vec!(
+        (RuleResultKind::Message, vec!("a".to_string(), "b".to_string())),
+        (RuleResultKind::Message, vec!("c".to_string()))
+    ));
+    assert_eq!(
+        (second.data, third.data),
+        (expected_second, single_data(RuleResultKind::Message, vec!("c".to_string())))
+    )
+}
+
 //msg
 
 #[test]
@@ -188,7 +227,7 @@ fn random() {
 
 #[test]
 fn choice() {
-    let mut parsed = parse("n -> msg choice Hi Hello");
+    let mut parsed = parse("n -> repeat 2 msg choice Hi Hello");
     parsed.advance(&());
     let adv = parsed.advance(&());
     assert_eq!((adv.res_type, adv.data),
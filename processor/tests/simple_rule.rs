@@ -1,8 +1,8 @@
 use std::str::FromStr;
 use chrono::{NaiveDate, NaiveTime};
 use common::{Advancing, AdvancingResultType};
-use common::event::{Event, EventPartValue};
-use common::simple_rule::{SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, WrappedSimpleRulePart};
+use common::event::{Duration, Event, EventPartValue, ListItem};
+use common::simple_rule::{SimpleRuleBorder, SimpleRulePart, SimpleRulePartValue, SimpleRuleSeq, SimpleRuleStat, WrappedSimpleRulePart};
 
 fn default_event() -> Event {
     let parts = [
@@ -21,11 +21,26 @@ fn default_event() -> Event {
     Event::new(parts)
 }
 
+fn achieving_event(val: u32) -> Event {
+    let mut event = default_event();
+    event.parts.insert("achieving".to_string(), EventPartValue::NumberBased(val));
+    event
+}
+
+fn advance_achieving(rule: &str, vals: &[u32]) -> Vec<AdvancingResultType> {
+    let mut rule = parser::parse_simple_rule(rule).unwrap().rule;
+    vals.iter()
+        .map(|v| rule.advance(&achieving_event(*v)).res_type)
+        .collect()
+}
+
 fn clear_og_vals(part: &mut WrappedSimpleRulePart) {
     match part {
         WrappedSimpleRulePart::Number(ref mut p) => p.og_values.clear(),
         WrappedSimpleRulePart::Time(ref mut p) => p.og_values.clear(),
-        WrappedSimpleRulePart::Date(ref mut p) => p.og_values.clear()
+        WrappedSimpleRulePart::Date(ref mut p) => p.og_values.clear(),
+        WrappedSimpleRulePart::Duration(ref mut p) => p.og_values.clear(),
+        WrappedSimpleRulePart::List(ref mut p) => p.og_values.clear()
     }
 }
 
@@ -503,6 +518,54 @@ fn mixed_date() {
     assert_res(res, (AdvancingResultType::Hit, Some(expected_part)))
 }
 
+fn advance_on(rule: &str, date: &str) -> AdvancingResultType {
+    let mut event = default_event();
+    event.parts.insert("on".to_string(), EventPartValue::DateBased(NaiveDate::from_str(date).unwrap()));
+    parser::parse_simple_rule(rule)
+        .unwrap()
+        .rule
+        .advance(&event)
+        .res_type
+}
+
+#[test]
+fn yearly_pattern_date() {
+    assert_eq!(advance_on("n: on *.12.24", "2031-12-24"), AdvancingResultType::Completed)
+}
+
+#[test]
+fn yearly_pattern_failed_date() {
+    assert_eq!(advance_on("n: on *.12.24", "2031-12-25"), AdvancingResultType::None)
+}
+
+#[test]
+fn monthly_pattern_date() {
+    assert_eq!(advance_on("n: on *.*.01", "2024-07-01"), AdvancingResultType::Completed)
+}
+
+#[test]
+fn wrapping_pattern_date() {
+    let rule = "n: on *.12.20..*.01.06";
+    assert_eq!(advance_on(rule, "2024-12-31"), AdvancingResultType::Completed);
+    assert_eq!(advance_on(rule, "2025-01-06"), AdvancingResultType::Completed);
+    assert_eq!(advance_on(rule, "2025-01-07"), AdvancingResultType::None);
+    assert_eq!(advance_on(rule, "2024-12-19"), AdvancingResultType::None)
+}
+
+#[test]
+fn plain_pattern_date() {
+    let rule = "n: on *.06.01..*.08.31";
+    assert_eq!(advance_on(rule, "2024-07-15"), AdvancingResultType::Completed);
+    assert_eq!(advance_on(rule, "2024-09-01"), AdvancingResultType::None)
+}
+
+#[test]
+fn monthly_wrapping_pattern_date() {
+    let rule = "n: on *.*.28..*.*.02";
+    assert_eq!(advance_on(rule, "2024-03-01"), AdvancingResultType::Completed);
+    assert_eq!(advance_on(rule, "2024-03-15"), AdvancingResultType::None)
+}
+
 //time
 
 #[test]
@@ -780,6 +843,17 @@ fn more_eq_result() {
     assert_res(res, (AdvancingResultType::Completed, Some(expected_part)))
 }
 
+#[test]
+fn more_eq_equal_result() {
+    let res = setup_and_advance("n: achieving >=1", "achieving");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 1, None)
+        ))
+    );
+    assert_res(res, (AdvancingResultType::Completed, Some(expected_part)))
+}
+
 #[test]
 fn between_result() {
     let res = setup_and_advance("n: achieving 0..2", "achieving");
@@ -803,6 +877,332 @@ fn mixed_result() {
     assert_res(res, (AdvancingResultType::Hit, Some(expected_part)))
 }
 
+#[test]
+fn more_eq_border_result() {
+    let res = setup_and_advance("n: achieving >=1", "achieving");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 1, None)
+        ))
+    );
+    assert_res(res, (AdvancingResultType::Completed, Some(expected_part)))
+}
+
+#[test]
+fn modulo_result() {
+    let res = setup_and_advance("n: achieving %1==0", "achieving");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Modulo, 1, Some(0))
+        ))
+    );
+    assert_res(res, (AdvancingResultType::Completed, Some(expected_part)))
+}
+
+#[test]
+fn modulo_failed_result() {
+    let res = setup_and_advance("n: achieving %2", "achieving");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Any, vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::Modulo, 2, Some(0))
+        ))
+    );
+    assert_res(res, (AdvancingResultType::None, Some(expected_part)))
+}
+
+#[test]
+fn modulo_progression_result() {
+    let res = advance_achieving("n: achieving %5==2 repeat +", &[2, 5, 12, 17]);
+    assert_eq!(res, vec!(
+        AdvancingResultType::Restarted, AdvancingResultType::None,
+        AdvancingResultType::Restarted, AdvancingResultType::Restarted
+    ))
+}
+
+//nth
+
+#[test]
+fn nth_action() {
+    let mut rule = parser::parse_simple_rule("n: did every(3rd) repeat +").unwrap().rule;
+    let res: Vec<_> = (0..6)
+        .map(|_| rule.advance(&default_event()).res_type)
+        .collect();
+    assert_eq!(res, vec!(
+        AdvancingResultType::Hit, AdvancingResultType::Hit, AdvancingResultType::Restarted,
+        AdvancingResultType::Hit, AdvancingResultType::Hit, AdvancingResultType::Restarted
+    ))
+}
+
+#[test]
+fn nth_values_action() {
+    let res = setup_and_advance("n: did every(1st,2)", "did");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Nth(1, 0), vec!(
+            SimpleRulePartValue::exact(2)
+        ))
+    );
+    assert_res(res, (AdvancingResultType::None, Some(expected_part)))
+}
+
+#[test]
+fn nth_ignores_nonmatching_event() {
+    let res = setup_and_advance("n: player 2 did every(1st)", "did");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Nth(1, 0), vec!())
+    );
+    assert_res(res, (AdvancingResultType::None, Some(expected_part)))
+}
+
+//stat
+
+#[test]
+fn stat_window_not_full() {
+    let res = advance_achieving("n: achieving avg(>=80) last 3", &[100, 100]);
+    assert_eq!(res, vec!(AdvancingResultType::Hit, AdvancingResultType::Hit))
+}
+
+#[test]
+fn avg_stat_completed() {
+    let res = advance_achieving("n: achieving avg(>=80) last 3", &[70, 90, 85]);
+    assert_eq!(res.last(), Some(&AdvancingResultType::Completed))
+}
+
+#[test]
+fn avg_stat_failed() {
+    let res = advance_achieving("n: achieving avg(>=80) last 3", &[70, 80, 85]);
+    assert_eq!(res.last(), Some(&AdvancingResultType::Hit))
+}
+
+#[test]
+fn median_stat_fractional() {
+    let res = advance_achieving("n: achieving median(>79) last 2", &[79, 80]);
+    assert_eq!(res.last(), Some(&AdvancingResultType::Completed))
+}
+
+#[test]
+fn min_stat_sliding() {
+    let res = advance_achieving("n: achieving min(>=50) last 2", &[40, 60, 70]);
+    assert_eq!(res, vec!(AdvancingResultType::Hit, AdvancingResultType::Hit, AdvancingResultType::Completed))
+}
+
+#[test]
+fn max_stat_restarted() {
+    let mut rule = parser::parse_simple_rule("n: achieving max(>=90) last 2 repeat +").unwrap().rule;
+    rule.advance(&achieving_event(95));
+    let adv_res = rule.advance(&achieving_event(10));
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Stat(SimpleRuleStat::Max, 2, vec!()), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 90, None)
+        ))
+    );
+    assert_res((adv_res.res_type, rule.parts.remove("achieving")),
+               (AdvancingResultType::Restarted, Some(expected_part)))
+}
+
+#[test]
+fn stat_ignores_nonmatching_event() {
+    let res = setup_and_advance("n: did 2 achieving max(>=1) last 1", "achieving");
+    let expected_part = WrappedSimpleRulePart::Number(
+        SimpleRulePart::new(SimpleRuleSeq::Stat(SimpleRuleStat::Max, 1, vec!()), vec!(
+            SimpleRulePartValue::new(SimpleRuleBorder::GreaterEq, 1, None)
+        ))
+    );
+    assert_res(res, (AdvancingResultType::None, Some(expected_part)))
+}
+
+//history
+
+fn advance_history(rule: &str, vals: &[(u32, u32)]) -> Vec<AdvancingResultType> {
+    let mut rule = parser::parse_simple_rule(rule).unwrap().rule;
+    vals.iter()
+        .map(|(of, achieving)| {
+            let mut event = achieving_event(*achieving);
+            event.parts.insert("of".to_string(), EventPartValue::NumberBased(*of));
+            rule.advance(&event).res_type
+        })
+        .collect()
+}
+
+#[test]
+fn best_baseline() {
+    let res = advance_history("n: achieving best", &[(1, 50)]);
+    assert_eq!(res, vec!(AdvancingResultType::None))
+}
+
+#[test]
+fn best_completed() {
+    let res = advance_history("n: achieving best repeat +", &[(1, 50), (1, 40), (1, 60), (1, 55), (1, 70)]);
+    assert_eq!(res, vec!(
+        AdvancingResultType::None, AdvancingResultType::None, AdvancingResultType::Restarted,
+        AdvancingResultType::None, AdvancingResultType::Restarted
+    ))
+}
+
+#[test]
+fn best_grouped() {
+    let res = advance_history("n: achieving best by of repeat +", &[(1, 50), (2, 40), (2, 45), (1, 45)]);
+    assert_eq!(res, vec!(
+        AdvancingResultType::None, AdvancingResultType::None,
+        AdvancingResultType::Restarted, AdvancingResultType::None
+    ))
+}
+
+#[test]
+fn improved_any() {
+    let res = advance_history("n: achieving improved repeat +", &[(1, 50), (1, 50), (1, 40), (1, 41)]);
+    assert_eq!(res, vec!(
+        AdvancingResultType::None, AdvancingResultType::None,
+        AdvancingResultType::None, AdvancingResultType::Restarted
+    ))
+}
+
+#[test]
+fn improved_margin() {
+    let res = advance_history("n: achieving improved(>=10)", &[(1, 50), (1, 55), (1, 65)]);
+    assert_eq!(res, vec!(
+        AdvancingResultType::None, AdvancingResultType::None, AdvancingResultType::Completed
+    ))
+}
+
+#[test]
+fn history_ignores_nonmatching_event() {
+    let mut rule = parser::parse_simple_rule("n: did 2 achieving best").unwrap().rule;
+    let adv_res = rule.advance(&achieving_event(50));
+    assert_eq!((adv_res.res_type, rule.memory.is_empty()), (AdvancingResultType::None, true))
+}
+
+//duration
+
+fn advance_took(rule: &str, secs: u32) -> AdvancingResultType {
+    let mut event = default_event();
+    event.parts.insert("took".to_string(), EventPartValue::DurationBased(Duration(secs)));
+    parser::parse_simple_rule(rule)
+        .unwrap()
+        .rule
+        .advance(&event)
+        .res_type
+}
+
+#[test]
+fn less_duration() {
+    assert_eq!(advance_took("n: took <5m", 299), AdvancingResultType::Completed)
+}
+
+#[test]
+fn less_failed_duration() {
+    assert_eq!(advance_took("n: took <5m", 300), AdvancingResultType::None)
+}
+
+#[test]
+fn between_duration() {
+    assert_eq!(advance_took("n: took 30s..2h", 3600), AdvancingResultType::Completed)
+}
+
+#[test]
+fn between_failed_duration() {
+    assert_eq!(advance_took("n: took 30s..2h", 29), AdvancingResultType::None)
+}
+
+#[test]
+fn numeric_duration() {
+    let mut event = default_event();
+    event.parts.insert("took".to_string(), EventPartValue::NumberBased(299));
+    let adv = parser::parse_simple_rule("n: took <5m")
+        .unwrap()
+        .rule
+        .advance(&event);
+    assert_eq!(adv.res_type, AdvancingResultType::Completed)
+}
+
+#[test]
+fn mismatched_value_type() {
+    let mut event = default_event();
+    event.parts.insert("took".to_string(), EventPartValue::TimeBased(NaiveTime::from_str("10:00").unwrap()));
+    let adv = parser::parse_simple_rule("n: took <5m")
+        .unwrap()
+        .rule
+        .advance(&event);
+    assert_eq!(adv.res_type, AdvancingResultType::None)
+}
+
+#[test]
+fn all_duration() {
+    assert_eq!(advance_took("n: took all(>1m,<1h)", 90), AdvancingResultType::Hit)
+}
+
+//list
+
+fn advance_tags(rule: &str, tags: &[&str]) -> AdvancingResultType {
+    let mut event = default_event();
+    let tags = tags.iter()
+        .map(|t| t.parse::<ListItem>().unwrap())
+        .collect();
+    event.parts.insert("tags".to_string(), EventPartValue::List(tags));
+    parser::parse_simple_rule(rule)
+        .unwrap()
+        .rule
+        .advance(&event)
+        .res_type
+}
+
+#[test]
+fn contains_list() {
+    assert_eq!(advance_tags("n: tags contains(loops)", &["arrays", "loops"]), AdvancingResultType::Completed)
+}
+
+#[test]
+fn contains_failed_list() {
+    assert_eq!(advance_tags("n: tags contains(loops)", &["arrays"]), AdvancingResultType::None)
+}
+
+#[test]
+fn contains_all_list() {
+    assert_eq!(advance_tags("n: tags contains_all(1,3)", &["1", "2", "3"]), AdvancingResultType::Completed)
+}
+
+#[test]
+fn contains_all_failed_list() {
+    assert_eq!(advance_tags("n: tags contains_all(1,4)", &["1", "2", "3"]), AdvancingResultType::None)
+}
+
+#[test]
+fn contains_any_list() {
+    assert_eq!(advance_tags("n: tags contains_any(recursion,loops)", &["loops"]), AdvancingResultType::Completed)
+}
+
+#[test]
+fn size_list() {
+    assert_eq!(advance_tags("n: tags size(>=2)", &["arrays", "loops"]), AdvancingResultType::Completed)
+}
+
+#[test]
+fn size_failed_list() {
+    assert_eq!(advance_tags("n: tags size(3..5)", &["arrays", "loops"]), AdvancingResultType::None)
+}
+
+#[test]
+fn size_empty_list() {
+    assert_eq!(advance_tags("n: tags size(0)", &[]), AdvancingResultType::Completed)
+}
+
+#[test]
+fn size_all_conditions() {
+    assert_eq!(advance_tags("n: tags size(>=2,<=3)", &["a", "b", "c", "d"]), AdvancingResultType::None)
+}
+
+#[test]
+fn contains_numeric_text() {
+    let mut event = default_event();
+    let tests = serde_json::from_str(r#"{"List":["3","loops"]}"#).unwrap();
+    event.parts.insert("tests".to_string(), tests);
+    let adv = parser::parse_simple_rule("n: tests contains(3)")
+        .unwrap()
+        .rule
+        .advance(&event)
+        .res_type;
+    assert_eq!(adv, AdvancingResultType::Completed)
+}
+
 //repeat
 
 #[test]
@@ -849,6 +1249,47 @@ fn repeat_infinite() {
     assert_res(res, (AdvancingResultType::Restarted, Some(expected_part)))
 }
 
+//presence
+
+fn advance_default(rule: &str) -> AdvancingResultType {
+    parser::parse_simple_rule(rule)
+        .unwrap()
+        .rule
+        .advance(&default_event())
+        .res_type
+}
+
+#[test]
+fn lenient_missing_part() {
+    assert_eq!(advance_default("n: player 1 took 5"), AdvancingResultType::Completed)
+}
+
+#[test]
+fn strict_missing_part() {
+    assert_eq!(advance_default("n: strict player 1 took 5"), AdvancingResultType::None)
+}
+
+#[test]
+fn strict_present_parts() {
+    assert_eq!(advance_default("n: strict player 1 did 1"), AdvancingResultType::Completed)
+}
+
+#[test]
+fn strict_optional_missing_part() {
+    assert_eq!(advance_default("n: strict player 1 optional took 5"), AdvancingResultType::Completed)
+}
+
+#[test]
+fn strict_optional_nonmatching_part() {
+    assert_eq!(advance_default("n: strict player 1 optional did 2"), AdvancingResultType::None)
+}
+
+#[test]
+fn strict_unknown_event_part() {
+    // parts the rule doesn't mention, such as `with` or `at`, don't stop it from completing
+    assert_eq!(advance_default("n: strict player 1"), AdvancingResultType::Completed)
+}
+
 //rule
 
 #[test]